@@ -0,0 +1,537 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use uuid::Uuid;
+
+use p3::{
+    errors::ErroRPG,
+    rpg_game::{carga::RegraCarga, equipamento::Slot, gerador::TipoGerado, idioma::tr, itens::{ItemTipo, ListagemItens}, personagens::Classe, raridade::Raridade, Game},
+    traits::ItemComportamento,
+};
+
+use crate::cli::console::Console;
+
+pub const SAVE_PADRAO: &str = "save.json";
+
+/// O que o binario deve fazer, vindo da linha de comando.
+pub enum Comando {
+    /// Sem subcomando: abre os menus.
+    Interativo,
+    Ajuda,
+    NovoPersonagem { nome: String, classe: Classe },
+    ListarPersonagens { json: bool },
+    ListarItens { json: bool },
+    Inventario { personagem: String, json: bool },
+    Equipar { personagem: String, item: String, slot: Option<Slot> },
+    Desequipar { personagem: String, slot: Slot },
+    Equipamento { personagem: String, json: bool },
+    UsarItem { personagem: String, item: String },
+    Batalhar { desafiante: String, oponente: String, semente: Option<u64> },
+    Exportar { saida: Option<PathBuf> },
+    AbrirBau { personagem: String, bau: String },
+    DescartarItem { personagem: String, item: String },
+    RegraCarga { regra: Option<RegraCarga> },
+    GerarItem { tipo: TipoGerado, classe: Classe, nivel: u32, raridade: Option<Raridade>, semente: Option<u64> },
+}
+
+pub struct Invocacao {
+    pub save: PathBuf,
+    pub comando: Comando,
+}
+
+/// Opcoes aceitas por cada subcomando, alem de `--save`.
+const OPCOES: &[(&str, &[&str])] = &[
+    ("new-character", &["name", "class"]),
+    ("list-characters", &["json"]),
+    ("list-items", &["json"]),
+    ("inventory", &["character", "json"]),
+    ("equip", &["character", "item", "slot"]),
+    ("unequip", &["character", "slot"]),
+    ("equipment", &["character", "json"]),
+    ("use-item", &["character", "item"]),
+    ("battle", &["challenger", "opponent", "seed"]),
+    ("export", &["output"]),
+    ("open-chest", &["character", "chest"]),
+    ("drop-item", &["character", "item"]),
+    ("encumbrance", &["rule"]),
+    ("generate-item", &["type", "class", "level", "rarity", "seed"]),
+    ("help", &[]),
+];
+
+/// Opcoes que nao recebem valor.
+const FLAGS: &[&str] = &["json"];
+
+/// Le os argumentos (sem o nome do programa).
+/// `P3` e `P3 <save>` continuam abrindo os menus, como antes dos subcomandos.
+pub fn interpretar(argumentos: impl IntoIterator<Item = String>) -> Result<Invocacao, ErroRPG> {
+    let mut argumentos = argumentos.into_iter().peekable();
+
+    let subcomando = match argumentos.peek() {
+        Some(primeiro) if OPCOES.iter().any(|(nome, _)| nome == primeiro) => argumentos.next(),
+        _ => None,
+    };
+
+    let aceitas = subcomando
+        .as_deref()
+        .and_then(|nome| OPCOES.iter().find(|(subcomando, _)| *subcomando == nome))
+        .map(|(_, aceitas)| *aceitas)
+        .unwrap_or(&[]);
+
+    let contexto = subcomando.clone().unwrap_or_else(|| "P3".to_string());
+    let mut opcoes: HashMap<String, String> = HashMap::new();
+    let mut save = None;
+
+    while let Some(argumento) = argumentos.next() {
+        let Some(opcao) = argumento.strip_prefix("--") else {
+            // posicional: so o caminho do save no modo interativo
+            if subcomando.is_none() && save.is_none() {
+                save = Some(PathBuf::from(argumento));
+                continue;
+            }
+
+            return Err(ErroRPG::EntradaInvalida(tr!("cli.argumento_inesperado", contexto, argumento)));
+        };
+
+        let (nome, valor) = match opcao.split_once('=') {
+            Some((nome, valor)) => (nome, Some(valor.to_string())),
+            None => (opcao, None),
+        };
+
+        if nome != "save" && !aceitas.contains(&nome) {
+            return Err(ErroRPG::EntradaInvalida(tr!("cli.opcao_desconhecida", contexto, argumento)));
+        }
+
+        if FLAGS.contains(&nome) {
+            opcoes.insert(nome.to_string(), String::new());
+            continue;
+        }
+
+        let valor = match valor.or_else(|| argumentos.next()) {
+            Some(valor) => valor,
+            None => return Err(ErroRPG::EntradaInvalida(tr!("cli.opcao_sem_valor", argumento))),
+        };
+
+        if nome == "save" {
+            save = Some(PathBuf::from(valor));
+        }
+        else {
+            opcoes.insert(nome.to_string(), valor);
+        }
+    }
+
+    let opcional = |nome: &str| opcoes.get(nome).cloned();
+    let json = opcoes.contains_key("json");
+    let obrigatoria = |nome: &str| {
+        opcoes
+            .get(nome)
+            .cloned()
+            .ok_or_else(|| ErroRPG::EntradaInvalida(tr!("cli.opcao_obrigatoria", contexto, nome)))
+    };
+
+    let comando = match subcomando.as_deref() {
+        None => Comando::Interativo,
+        Some("help") => Comando::Ajuda,
+        Some("new-character") => Comando::NovoPersonagem {
+            nome: obrigatoria("name")?,
+            classe: interpretar_classe(&obrigatoria("class")?)?,
+        },
+        Some("list-characters") => Comando::ListarPersonagens { json },
+        Some("list-items") => Comando::ListarItens { json },
+        Some("inventory") => Comando::Inventario {
+            personagem: obrigatoria("character")?,
+            json,
+        },
+        Some("equip") => Comando::Equipar {
+            personagem: obrigatoria("character")?,
+            item: obrigatoria("item")?,
+            slot: opcional("slot").as_deref().map(interpretar_slot).transpose()?,
+        },
+        Some("unequip") => Comando::Desequipar {
+            personagem: obrigatoria("character")?,
+            slot: interpretar_slot(&obrigatoria("slot")?)?,
+        },
+        Some("equipment") => Comando::Equipamento {
+            personagem: obrigatoria("character")?,
+            json,
+        },
+        Some("use-item") => Comando::UsarItem {
+            personagem: obrigatoria("character")?,
+            item: obrigatoria("item")?,
+        },
+        Some("battle") => Comando::Batalhar {
+            desafiante: obrigatoria("challenger")?,
+            oponente: obrigatoria("opponent")?,
+            semente: opcional("seed").as_deref().map(interpretar_semente).transpose()?,
+        },
+        Some("export") => Comando::Exportar {
+            saida: opcional("output").map(PathBuf::from),
+        },
+        Some("open-chest") => Comando::AbrirBau {
+            personagem: obrigatoria("character")?,
+            bau: obrigatoria("chest")?,
+        },
+        Some("drop-item") => Comando::DescartarItem {
+            personagem: obrigatoria("character")?,
+            item: obrigatoria("item")?,
+        },
+        Some("encumbrance") => Comando::RegraCarga {
+            regra: opcional("rule").as_deref().map(interpretar_regra_carga).transpose()?,
+        },
+        Some("generate-item") => Comando::GerarItem {
+            tipo: interpretar_tipo(&obrigatoria("type")?)?,
+            classe: interpretar_classe(&obrigatoria("class")?)?,
+            nivel: match opcional("level") {
+                Some(nivel) => nivel
+                    .parse()
+                    .map_err(|_| ErroRPG::EntradaInvalida(tr!("cli.nivel_invalido", nivel)))?,
+                None => 1,
+            },
+            raridade: opcional("rarity")
+                .map(|raridade| {
+                    Raridade::de_texto(&raridade)
+                        .ok_or_else(|| ErroRPG::EntradaInvalida(tr!("cli.raridade_desconhecida", raridade)))
+                })
+                .transpose()?,
+            semente: opcional("seed").as_deref().map(interpretar_semente).transpose()?,
+        },
+        Some(outro) => return Err(ErroRPG::EntradaInvalida(tr!("cli.comando_desconhecido", outro))),
+    };
+
+    Ok(Invocacao {
+        save: save.unwrap_or_else(|| PathBuf::from(SAVE_PADRAO)),
+        comando,
+    })
+}
+
+/// Aceita o nome da classe em portugues ou ingles, sem diferenciar maiusculas.
+fn interpretar_classe(texto: &str) -> Result<Classe, ErroRPG> {
+    match texto.to_lowercase().as_str() {
+        "guerreiro" | "warrior" => Ok(Classe::Guerreiro),
+        "mago" | "mage" => Ok(Classe::Mago),
+        "assassino" | "assassin" => Ok(Classe::Assassino),
+        _ => Err(ErroRPG::EntradaInvalida(tr!("cli.classe_desconhecida", texto))),
+    }
+}
+
+fn interpretar_tipo(texto: &str) -> Result<TipoGerado, ErroRPG> {
+    match texto.to_lowercase().as_str() {
+        "arma" | "weapon" => Ok(TipoGerado::Arma),
+        "armadura" | "armor" => Ok(TipoGerado::Armadura),
+        _ => Err(ErroRPG::EntradaInvalida(tr!("cli.tipo_desconhecido", texto))),
+    }
+}
+
+fn interpretar_slot(texto: &str) -> Result<Slot, ErroRPG> {
+    Slot::de_texto(texto).ok_or_else(|| ErroRPG::EntradaInvalida(tr!("cli.slot_desconhecido", texto)))
+}
+
+fn interpretar_regra_carga(texto: &str) -> Result<RegraCarga, ErroRPG> {
+    match texto.to_lowercase().as_str() {
+        "refuse" | "recusar" => Ok(RegraCarga::RecusarItem),
+        "penalty" | "penalidade" => Ok(RegraCarga::Penalidade),
+        _ => Err(ErroRPG::EntradaInvalida(tr!("cli.regra_desconhecida", texto))),
+    }
+}
+
+fn interpretar_semente(texto: &str) -> Result<u64, ErroRPG> {
+    texto
+        .parse()
+        .map_err(|_| ErroRPG::EntradaInvalida(tr!("cli.semente_invalida", texto)))
+}
+
+/// Executa um subcomando sobre o jogo carregado.
+/// Devolve `true` se o jogo mudou e precisa ser salvo.
+pub fn executar(game: &mut Game, comando: Comando, console: &mut Console) -> Result<bool, ErroRPG> {
+    match comando {
+        Comando::Interativo | Comando::Ajuda => {
+            console.escrever_linha(&tr!("cli.uso"));
+            Ok(false)
+        }
+        Comando::NovoPersonagem { nome, classe } => {
+            let id = game.novo_personagem(nome, classe);
+            console.escrever_linha(&id.to_string());
+            Ok(true)
+        }
+        Comando::ListarPersonagens { json } => {
+            let mut personagens: Vec<_> = game.personagens().collect();
+            personagens.sort_by(|a, b| a.nome.cmp(&b.nome));
+
+            if json {
+                console.escrever_linha(&serde_json::to_string_pretty(&personagens)?);
+                return Ok(false);
+            }
+
+            for personagem in personagens {
+                console.escrever_linha(&format!(
+                    "{}\t{}\t{}\t{}\t{}/{}\t{}/{}",
+                    personagem.id,
+                    personagem.nome,
+                    personagem.classe.as_str(),
+                    personagem.nivel,
+                    personagem.vida,
+                    personagem.vida_maxima,
+                    personagem.peso_carregado(),
+                    personagem.capacidade_carga(),
+                ));
+            }
+            Ok(false)
+        }
+        Comando::ListarItens { json } => {
+            escrever_listagem(&game.listar_itens(), json, console)?;
+            Ok(false)
+        }
+        Comando::Inventario { personagem, json } => {
+            let personagem = game.personagem(resolver_personagem(game, &personagem)?)?;
+            escrever_listagem(&personagem.listar_inventario(), json, console)?;
+            Ok(false)
+        }
+        Comando::Equipar { personagem, item, slot } => {
+            let personagem = resolver_personagem(game, &personagem)?;
+            let item = resolver_item_para_equipar(game, personagem, &item)?;
+
+            let slot = game.equipar(personagem, item, slot)?;
+
+            let personagem = game.personagem(personagem)?;
+            let nome = personagem.equipamento.get(slot).map(ItemTipo::get_nome).unwrap_or_default();
+            console.escrever_linha(&tr!("cli.equipado", personagem.nome, nome, slot.nome()));
+            Ok(true)
+        }
+        Comando::Desequipar { personagem, slot } => {
+            let personagem = resolver_personagem(game, &personagem)?;
+            let item = game.desequipar(personagem, slot)?;
+
+            console.escrever_linha(&tr!("cli.desequipado", game.personagem(personagem)?.nome, item.get_nome()));
+            Ok(true)
+        }
+        Comando::Equipamento { personagem, json } => {
+            let personagem = game.personagem(resolver_personagem(game, &personagem)?)?;
+
+            if json {
+                console.escrever_linha(&serde_json::to_string_pretty(&personagem.equipamento)?);
+                return Ok(false);
+            }
+
+            for (slot, item) in personagem.equipamento.itens() {
+                console.escrever_linha(&format!("{}\t{}\t{}\t{}", slot.as_str(), item.get_id(), item.get_tipo(), item.get_nome()));
+            }
+            Ok(false)
+        }
+        Comando::UsarItem { personagem, item } => {
+            let personagem = resolver_personagem(game, &personagem)?;
+            let item = resolver_item_do_inventario(game, personagem, &item)?;
+
+            if !matches!(game.personagem(personagem)?.inventario.get(&item), Some(ItemTipo::Consumivel(_))) {
+                return Err(ErroRPG::ItemNaoUsavel(tr!("cli.item_nao_consumivel", item)));
+            }
+
+            game.usar_item(personagem, item)?;
+
+            let personagem = game.personagem(personagem)?;
+            console.escrever_linha(&tr!("cli.item_usado", personagem.nome, personagem.vida, personagem.vida_maxima));
+            Ok(true)
+        }
+        Comando::Batalhar { desafiante, oponente, semente } => {
+            let desafiante = resolver_personagem(game, &desafiante)?;
+            let oponente = resolver_personagem(game, &oponente)?;
+
+            if let Some(semente) = semente {
+                game.definir_semente(semente);
+            }
+
+            let resultado = game.batalhar(desafiante, oponente)?;
+
+            for registro in &resultado.log {
+                console.escrever_linha(&tr!("batalha.rodada", registro.rodada));
+                for evento in &registro.eventos {
+                    console.escrever_linha(&evento.descrever());
+                }
+            }
+
+            match resultado.vencedor {
+                Some(vencedor) => {
+                    console.escrever_linha(&tr!(
+                        "batalha.vitoria",
+                        game.personagem(vencedor)?.nome, resultado.rodadas, resultado.experiencia
+                    ));
+                    for nivel in &resultado.niveis {
+                        console.escrever_linha(&nivel.descrever());
+                    }
+                    for linha in resultado.saque.descrever(&game.personagem(vencedor)?.nome) {
+                        console.escrever_linha(&linha);
+                    }
+                }
+                None => console.escrever_linha(&tr!("batalha.empate", resultado.rodadas)),
+            }
+            Ok(true)
+        }
+        Comando::Exportar { saida } => {
+            let conteudo = serde_json::to_string_pretty(&game.exportar()?)?;
+
+            match saida {
+                Some(path) => fs::write(path, conteudo)?,
+                None => console.escrever_linha(&conteudo),
+            }
+            Ok(false)
+        }
+        Comando::AbrirBau { personagem, bau } => {
+            let personagem = resolver_personagem(game, &personagem)?;
+            let saque = game.abrir_bau(personagem, &bau)?;
+
+            for linha in saque.descrever(&game.personagem(personagem)?.nome) {
+                console.escrever_linha(&linha);
+            }
+            Ok(true)
+        }
+        Comando::DescartarItem { personagem, item } => {
+            let personagem = resolver_personagem(game, &personagem)?;
+            let item = resolver_item_do_inventario(game, personagem, &item)?;
+
+            let descartado = game.descartar_item(personagem, item)?;
+
+            console.escrever_linha(&tr!("cli.descartado", game.personagem(personagem)?.nome, descartado.nome_com_quantidade()));
+            Ok(true)
+        }
+        Comando::RegraCarga { regra } => {
+            if let Some(regra) = regra {
+                game.definir_regra_carga(regra);
+            }
+
+            console.escrever_linha(&tr!("cli.regra_carga", game.regra_carga().nome()));
+            Ok(regra.is_some())
+        }
+        Comando::GerarItem { tipo, classe, nivel, raridade, semente } => {
+            if let Some(semente) = semente {
+                game.definir_semente(semente);
+            }
+
+            let item = match tipo {
+                TipoGerado::Arma => ItemTipo::Arma(game.gerar_arma(&classe, nivel, raridade)),
+                TipoGerado::Armadura => ItemTipo::Armadura(game.gerar_armadura(&classe, nivel, raridade)),
+            };
+
+            console.escrever_linha(&item.get_id().to_string());
+            console.escrever_linha(&item.exibir_descricao());
+            Ok(true)
+        }
+    }
+}
+
+/// Em texto, uma linha por item: ID, tipo e nome (com a pilha, nos consumiveis) separados por tabulacao.
+fn escrever_listagem(listagem: &ListagemItens, json: bool, console: &mut Console) -> Result<(), ErroRPG> {
+    if json {
+        console.escrever_linha(&serde_json::to_string_pretty(listagem)?);
+        return Ok(());
+    }
+
+    let equipamentos = listagem.armaduras.iter().map(|item| *item as &dyn ItemComportamento)
+        .chain(listagem.armas.iter().map(|item| *item as &dyn ItemComportamento));
+
+    for item in equipamentos {
+        console.escrever_linha(&format!("{}\t{}\t{}", item.get_id(), item.get_tipo(), item.get_nome()));
+    }
+
+    for item in &listagem.consumiveis {
+        console.escrever_linha(&format!("{}\t{}\t{}", item.get_id(), item.get_tipo(), item.nome_com_quantidade()));
+    }
+
+    Ok(())
+}
+
+/// Aceita o ID do personagem ou o nome dele, se for unico.
+fn resolver_personagem(game: &Game, referencia: &str) -> Result<Uuid, ErroRPG> {
+    if let Ok(id) = Uuid::parse_str(referencia) {
+        return game.personagem(id).map(|personagem| personagem.id);
+    }
+
+    let mut encontrados = game.personagens().filter(|personagem| personagem.nome == referencia);
+
+    match (encontrados.next(), encontrados.next()) {
+        (Some(personagem), None) => Ok(personagem.id),
+        (Some(_), Some(_)) => Err(ErroRPG::EntradaInvalida(tr!("cli.personagem_ambiguo", referencia))),
+        (None, _) => Err(ErroRPG::EntradaInvalida(tr!("cli.personagem_desconhecido", referencia))),
+    }
+}
+
+/// Item do inventario do personagem, pelo ID ou pelo nome.
+fn resolver_item_do_inventario(game: &Game, personagem: Uuid, referencia: &str) -> Result<Uuid, ErroRPG> {
+    game.personagem(personagem)?
+        .inventario
+        .values()
+        .find(|item| item.get_id().to_string() == referencia || item.get_nome() == referencia)
+        .map(|item| item.get_id())
+        .ok_or_else(|| ErroRPG::ItemNaoEncontrado(referencia.to_string()))
+}
+
+/// Arma ou armadura a equipar. Se nao estiver no inventario, é pega do catalogo.
+fn resolver_item_para_equipar(game: &mut Game, personagem: Uuid, referencia: &str) -> Result<Uuid, ErroRPG> {
+    let item = match resolver_item_do_inventario(game, personagem, referencia) {
+        Ok(item) => item,
+        Err(_) => {
+            let item = game
+                .itens()
+                .find(|item| item.get_id().to_string() == referencia || item.get_nome() == referencia)
+                .map(|item| item.get_id())
+                .ok_or_else(|| ErroRPG::ItemNaoEncontrado(referencia.to_string()))?;
+
+            game.adicionar_ao_inventario(personagem, item)?
+        }
+    };
+
+    match game.personagem(personagem)?.inventario.get(&item) {
+        Some(ItemTipo::Arma(_) | ItemTipo::Armadura(_)) => Ok(item),
+        _ => Err(ErroRPG::ItemNaoUsavel(tr!("cli.item_nao_equipavel", referencia))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(linha: &str) -> Result<Invocacao, ErroRPG> {
+        interpretar(linha.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn sem_subcomando_abre_os_menus() {
+        let invocacao = args("").unwrap();
+        assert!(matches!(invocacao.comando, Comando::Interativo));
+        assert_eq!(invocacao.save, PathBuf::from(SAVE_PADRAO));
+
+        let invocacao = args("outro.json").unwrap();
+        assert!(matches!(invocacao.comando, Comando::Interativo));
+        assert_eq!(invocacao.save, PathBuf::from("outro.json"));
+    }
+
+    #[test]
+    fn opcoes_com_espaco_ou_igual() {
+        let invocacao = args("equip --character Ana --item=Espada --slot off-hand --save=slot.json").unwrap();
+
+        assert_eq!(invocacao.save, PathBuf::from("slot.json"));
+        assert!(matches!(
+            invocacao.comando,
+            Comando::Equipar { personagem, item, slot: Some(Slot::MaoSecundaria) } if personagem == "Ana" && item == "Espada"
+        ));
+
+        let invocacao = args("generate-item --type weapon --class warrior --level 7 --rarity epic --seed 3").unwrap();
+        assert!(matches!(
+            invocacao.comando,
+            Comando::GerarItem { tipo: TipoGerado::Arma, classe: Classe::Guerreiro, nivel: 7, raridade: Some(Raridade::Epico), semente: Some(3) }
+        ));
+
+        assert!(matches!(args("list-items --json").unwrap().comando, Comando::ListarItens { json: true }));
+    }
+
+    #[test]
+    fn argumentos_invalidos_sao_recusados() {
+        for linha in [
+            "new-character --name Ana",
+            "new-character --name Ana --class bardo",
+            "list-items --character Ana",
+            "battle --challenger",
+            "inventory Ana",
+            "generate-item --type weapon --class mage --level alto",
+            "unequip --character Ana --slot cauda",
+        ] {
+            assert!(matches!(args(linha), Err(ErroRPG::EntradaInvalida(_))), "{linha}");
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+
+use uuid::Uuid;
+
+use p3::{errors::ErroRPG, rpg_game::{idioma::tr, raridade::Raridade}};
+
+/// Entrada e saida dos menus do jogo.
+/// Aceita qualquer leitor e escritor, entao os fluxos interativos podem ser dirigidos
+/// por uma entrada roteirizada (testes) ou por outra interface em vez do terminal.
+pub struct Console<'a> {
+    entrada: Box<dyn BufRead + 'a>,
+    saida: Box<dyn Write + 'a>,
+    /// Se a saida aceita cores ANSI. So o terminal aceita; arquivos e testes recebem texto puro.
+    cores: bool,
+}
+
+impl<'a> Console<'a> {
+    pub fn new(entrada: impl BufRead + 'a, saida: impl Write + 'a) -> Self {
+        Console {
+            entrada: Box::new(entrada),
+            saida: Box::new(saida),
+            cores: false,
+        }
+    }
+
+    /// Console ligado ao terminal (stdin/stdout). Usa cores se o stdout for mesmo um terminal.
+    pub fn padrao() -> Console<'static> {
+        let mut console = Console::new(BufReader::new(io::stdin()), io::stdout());
+        console.cores = io::stdout().is_terminal();
+        console
+    }
+
+    /// Nome da raridade, pintado com a cor dela quando a saida aceita cores.
+    pub fn raridade(&self, raridade: Raridade) -> String {
+        if self.cores {
+            raridade.nome_colorido()
+        } else {
+            raridade.nome()
+        }
+    }
+
+    /// Escreve sem quebra de linha, para prompts.
+    pub fn escrever(&mut self, texto: &str) {
+        let _ = write!(self.saida, "{}", texto);
+        let _ = self.saida.flush();
+    }
+
+    pub fn escrever_linha(&mut self, texto: &str) {
+        let _ = writeln!(self.saida, "{}", texto);
+    }
+
+    /// Le uma linha sem espacos nas pontas.
+    /// Devolve `ErroRPG::EntradaEncerrada` quando a entrada acaba.
+    pub fn ler_string(&mut self) -> Result<String, ErroRPG> {
+        let mut entrada = String::new();
+
+        let lidos = self.entrada.read_line(&mut entrada)?;
+
+        if lidos == 0 {
+            return Err(ErroRPG::EntradaEncerrada);
+        }
+
+        Ok(entrada.trim().to_string())
+    }
+
+    pub fn ler_u32(&mut self) -> Result<u32, ErroRPG> {
+        self.ler_string()?
+            .parse::<u32>()
+            .map_err(|_| ErroRPG::EntradaInvalida(tr!("entrada.inteiro_positivo")))
+    }
+
+    pub fn ler_i32(&mut self) -> Result<i32, ErroRPG> {
+        self.ler_string()?
+            .parse::<i32>()
+            .map_err(|_| ErroRPG::EntradaInvalida(tr!("entrada.inteiro")))
+    }
+
+    pub fn ler_f32(&mut self) -> Result<f32, ErroRPG> {
+        self.ler_string()?
+            .replace(",", ".") // permite usar "1,5" ou "1.5"
+            .parse::<f32>()
+            .map_err(|_| ErroRPG::EntradaInvalida(tr!("entrada.decimal")))
+    }
+
+    pub fn ler_uuid(&mut self) -> Result<Uuid, ErroRPG> {
+        let texto = self.ler_string()?;
+
+        Uuid::parse_str(&texto)
+            .map_err(|_| ErroRPG::EntradaInvalida(tr!("entrada.uuid", texto)))
+    }
+
+    pub fn ler_string_loop(&mut self, prompt: &str) -> Result<String, ErroRPG> {
+        self.escrever(prompt);
+        self.ler_string()
+    }
+
+    pub fn ler_u32_loop(&mut self, prompt: &str) -> Result<u32, ErroRPG> {
+        self.repetir(prompt, Self::ler_u32)
+    }
+
+    pub fn ler_i32_loop(&mut self, prompt: &str) -> Result<i32, ErroRPG> {
+        self.repetir(prompt, Self::ler_i32)
+    }
+
+    pub fn ler_f32_loop(&mut self, prompt: &str) -> Result<f32, ErroRPG> {
+        self.repetir(prompt, Self::ler_f32)
+    }
+
+    pub fn ler_uuid_loop(&mut self, prompt: &str) -> Result<Uuid, ErroRPG> {
+        self.repetir(prompt, Self::ler_uuid)
+    }
+
+    /// Mostra o prompt e le ate receber um valor valido.
+    /// So desiste se a entrada acabar ou falhar.
+    fn repetir<T>(&mut self, prompt: &str, ler: fn(&mut Self) -> Result<T, ErroRPG>) -> Result<T, ErroRPG> {
+        loop {
+            self.escrever(prompt);
+
+            match ler(self) {
+                Ok(valor) => return Ok(valor),
+                Err(ErroRPG::EntradaInvalida(s)) => self.escrever_linha(&s),
+                Err(erro) => return Err(erro),
+            }
+        }
+    }
+}
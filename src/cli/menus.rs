@@ -0,0 +1,754 @@
+use uuid::Uuid;
+
+use p3::{
+    errors::ErroRPG,
+    rpg_game::{equipamento::{Encaixe, Slot}, idioma::tr, itens::{Arma, Armadura, Consumivel, Efeito, ItemTipo}, personagens::{Classe, Personagem}, raridade::Raridade, Game},
+    traits::{Identificavel, ItemComportamento},
+};
+
+use crate::cli::console::Console;
+
+/// Encaixes oferecidos ao criar uma armadura, na ordem de `equipamento.menu_encaixe_armadura`.
+const ENCAIXES_ARMADURA: [Encaixe; 7] = [
+    Encaixe::Cabeca,
+    Encaixe::Peito,
+    Encaixe::Pernas,
+    Encaixe::Maos,
+    Encaixe::MaoSecundaria,
+    Encaixe::Anel,
+    Encaixe::Amuleto,
+];
+
+/// Encaixes oferecidos ao criar uma arma, na ordem de `equipamento.menu_encaixe_arma`.
+const ENCAIXES_ARMA: [Encaixe; 2] = [Encaixe::MaoPrincipal, Encaixe::MaoSecundaria];
+
+/// Menu principal. Retorna quando o jogador escolhe sair ou a entrada acaba;
+/// quem chamou é responsavel por salvar o jogo.
+pub fn iniciar_menu(game: &mut Game, console: &mut Console) -> Result<(), ErroRPG> {
+    loop {
+        let opcao = console.ler_u32_loop(&tr!("menu.principal"))?;
+
+        match opcao {
+            1 => menu_jogo(game, true, console)?,
+            2 => {
+                // o jogo é salvo ao sair, entao apagar os personagens por engano perderia o save
+                let total = game.personagens().count();
+                if total > 0 && console.ler_u32_loop(&tr!("menu.confirmar_novo_jogo", total))? != 1 {
+                    continue;
+                }
+
+                game.remover_personagens();
+                criar_personagem(game, false, console)?;
+                menu_jogo(game, false, console)?;
+            }
+            3 => {
+                if game.personagens().next().is_none() {
+                    console.escrever_linha(&tr!("menu.sem_save"));
+                    criar_personagem(game, false, console)?;
+                }
+                menu_jogo(game, false, console)?;
+            }
+            0 => return Ok(()),
+            _ => console.escrever_linha(&tr!("menu.opcao_invalida")),
+        }
+    }
+}
+
+fn menu_jogo(game: &mut Game, simulacao: bool, console: &mut Console) -> Result<(), ErroRPG> {
+    loop {
+        let opcao = console.ler_u32_loop(&tr!("menu.jogo"))?;
+
+        let resultado = match opcao {
+            1 => criar_personagem(game, simulacao, console).map(|_| ()),
+            2 => {
+                listar_personagens(game, console);
+                Ok(())
+            }
+            3 => menu_inventario(game, console),
+            4 => menu_criar_item(game, console),
+            5 => {
+                listar_todas_armas(game, console);
+                listar_todas_armaduras(game, console);
+                listar_todos_consumiveis(game, console);
+                Ok(())
+            }
+            6 => menu_batalha(game, console),
+            7 => abrir_bau(game, console),
+            0 => return Ok(()),
+            _ => {
+                console.escrever_linha(&tr!("menu.opcao_invalida"));
+                Ok(())
+            }
+        };
+
+        match resultado {
+            Err(ErroRPG::EntradaEncerrada) => return Err(ErroRPG::EntradaEncerrada),
+            Err(erro) => console.escrever_linha(&tr!("menu.erro", erro)),
+            Ok(()) => {}
+        }
+    }
+}
+
+fn menu_inventario(game: &mut Game, console: &mut Console) -> Result<(), ErroRPG> {
+    let id = escolher_personagem(game, console)?;
+
+    loop {
+        listar_inventario(game.personagem(id)?, console);
+
+        let opcao = console.ler_u32_loop(&tr!("menu.inventario"))?;
+
+        match opcao {
+            1 => {
+                let item = console.ler_uuid_loop(&tr!("prompt.id_item"))?;
+                match game.usar_item(id, item) {
+                    Ok(_) => console.escrever_linha(&tr!("inventario.item_usado")),
+                    Err(erro) => console.escrever_linha(&tr!("menu.erro", erro)),
+                }
+            }
+            2 => {
+                listar_todas_armas(game, console);
+                listar_todas_armaduras(game, console);
+                listar_todos_consumiveis(game, console);
+                let item = console.ler_uuid_loop(&tr!("prompt.id_item"))?;
+                match game.adicionar_ao_inventario(id, item) {
+                    Ok(_) => console.escrever_linha(&tr!("inventario.item_adicionado")),
+                    Err(erro) => console.escrever_linha(&tr!("menu.erro", erro)),
+                }
+            }
+            3 => {
+                let item = console.ler_uuid_loop(&tr!("prompt.id_item"))?;
+                match game.descartar_item(id, item) {
+                    Ok(_) => console.escrever_linha(&tr!("inventario.item_descartado")),
+                    Err(erro) => console.escrever_linha(&tr!("menu.erro", erro)),
+                }
+            }
+            4 => {
+                let slot = ler_slot(console)?;
+                match game.desequipar(id, slot) {
+                    Ok(_) => console.escrever_linha(&tr!("inventario.item_desequipado")),
+                    Err(erro) => console.escrever_linha(&tr!("menu.erro", erro)),
+                }
+            }
+            0 => return Ok(()),
+            _ => console.escrever_linha(&tr!("menu.opcao_invalida")),
+        }
+    }
+}
+
+fn menu_criar_item(game: &mut Game, console: &mut Console) -> Result<(), ErroRPG> {
+    let opcao = console.ler_u32_loop(&tr!("menu.criar_item"))?;
+
+    match opcao {
+        1 => { criar_arma(game, console)?; }
+        2 => { criar_armadura(game, console)?; }
+        3 => { criar_consumivel(game, console)?; }
+        4 => { gerar_item(game, console)?; }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn abrir_bau(game: &mut Game, console: &mut Console) -> Result<(), ErroRPG> {
+    let personagem = escolher_personagem(game, console)?;
+
+    let baus: Vec<String> = game.baus().into_iter().map(String::from).collect();
+    if baus.is_empty() {
+        console.escrever_linha(&tr!("saque.sem_baus"));
+        return Ok(());
+    }
+
+    console.escrever_linha(&tr!("saque.baus"));
+    for (i, bau) in baus.iter().enumerate() {
+        console.escrever_linha(&format!("[{}] {}", i, bau));
+    }
+
+    let bau = loop {
+        let opcao = console.ler_u32_loop(&tr!("saque.prompt_bau"))?;
+
+        match baus.get(opcao as usize) {
+            Some(bau) => break bau,
+            None => console.escrever_linha(&tr!("menu.opcao_invalida")),
+        }
+    };
+
+    let saque = game.abrir_bau(personagem, bau)?;
+
+    for linha in saque.descrever(&game.personagem(personagem)?.nome) {
+        console.escrever_linha(&linha);
+    }
+
+    Ok(())
+}
+
+fn menu_batalha(game: &mut Game, console: &mut Console) -> Result<(), ErroRPG> {
+    console.escrever_linha(&tr!("batalha.desafiante"));
+    let desafiante = escolher_personagem(game, console)?;
+    console.escrever_linha(&tr!("batalha.oponente"));
+    let oponente = escolher_personagem(game, console)?;
+
+    let resultado = game.batalhar(desafiante, oponente)?;
+
+    for registro in &resultado.log {
+        console.escrever_linha(&tr!("batalha.rodada", registro.rodada));
+        for evento in &registro.eventos {
+            console.escrever_linha(&evento.descrever());
+        }
+    }
+
+    match resultado.vencedor {
+        Some(vencedor) => {
+            console.escrever_linha(&tr!(
+                "batalha.vitoria",
+                game.personagem(vencedor)?.nome, resultado.rodadas, resultado.experiencia
+            ));
+            for nivel in &resultado.niveis {
+                console.escrever_linha(&nivel.descrever());
+            }
+            for linha in resultado.saque.descrever(&game.personagem(vencedor)?.nome) {
+                console.escrever_linha(&linha);
+            }
+        }
+        None => console.escrever_linha(&tr!("batalha.empate", resultado.rodadas)),
+    }
+
+    Ok(())
+}
+
+pub fn listar_personagens(game: &Game, console: &mut Console) {
+    console.escrever_linha(&tr!("listagem.personagens"));
+
+    if game.personagens().next().is_none() {
+        console.escrever_linha(&tr!("listagem.personagens.vazio"));
+    }
+
+    for personagem in game.personagens() {
+        console.escrever_linha(&tr!(
+            "listagem.personagem",
+            personagem.id,
+            personagem.nome,
+            personagem.classe.nome(),
+            personagem.nivel,
+            personagem.vida,
+            personagem.vida_maxima,
+            personagem.forca,
+            personagem.defesa_total(),
+            personagem.ouro,
+            personagem.get_arma().map_or_else(|| tr!("equipamento.vazio"), Arma::get_nome),
+            personagem.get_armadura().map_or_else(|| tr!("equipamento.vazio"), Armadura::get_nome),
+            personagem.peso_carregado(),
+            personagem.capacidade_carga(),
+        ));
+        console.escrever_linha(&tr!("menu.separador"));
+    }
+}
+
+fn escolher_personagem(game: &Game, console: &mut Console) -> Result<Uuid, ErroRPG> {
+    if game.personagens().next().is_none() {
+        return Err(ErroRPG::EntradaInvalida(tr!("personagem.nenhum_criado")));
+    }
+
+    listar_personagens(game, console);
+
+    loop {
+        let id = console.ler_uuid_loop(&tr!("prompt.id_personagem"))?;
+
+        if game.personagem(id).is_ok() {
+            return Ok(id);
+        }
+
+        console.escrever_linha(&tr!("personagem.id_inexistente"));
+    }
+}
+
+pub fn listar_inventario(personagem: &Personagem, console: &mut Console) {
+    escrever_equipamento(personagem, console);
+
+    let inventario = personagem.listar_inventario();
+
+    escrever_armaduras(&inventario.armaduras, "listagem.armaduras.vazio_inventario", console);
+    escrever_armas(&inventario.armas, "listagem.armas.vazio_inventario", console);
+    escrever_consumiveis(&inventario.consumiveis, "listagem.consumiveis.vazio_inventario", console);
+}
+
+pub fn listar_todas_armaduras(game: &Game, console: &mut Console) {
+    escrever_armaduras(&game.listar_itens().armaduras, "listagem.armaduras.vazio", console);
+}
+
+pub fn listar_todas_armas(game: &Game, console: &mut Console) {
+    escrever_armas(&game.listar_itens().armas, "listagem.armas.vazio", console);
+}
+
+pub fn listar_todos_consumiveis(game: &Game, console: &mut Console) {
+    escrever_consumiveis(&game.listar_itens().consumiveis, "listagem.consumiveis.vazio", console);
+}
+
+/// Todos os slots, inclusive os vazios.
+fn escrever_equipamento(personagem: &Personagem, console: &mut Console) {
+    console.escrever_linha(&tr!("listagem.equipamento"));
+
+    for slot in Slot::TODOS {
+        let item = personagem.equipamento.get(slot).map_or_else(|| tr!("equipamento.vazio"), ItemTipo::get_nome);
+        console.escrever_linha(&tr!("listagem.slot", slot.nome(), item));
+    }
+
+    console.escrever_linha(&tr!("menu.separador"));
+}
+
+/// `vazio` é a chave da mensagem mostrada quando nao ha nenhuma armadura.
+fn escrever_armaduras(armaduras: &[&Armadura], vazio: &str, console: &mut Console) {
+    console.escrever_linha(&tr!("listagem.armaduras"));
+
+    for armadura in armaduras {
+        console.escrever_linha(&tr!(
+            "listagem.armadura",
+            armadura.id(),
+            armadura.get_nome(),
+            armadura.get_defesa(),
+            console.raridade(armadura.get_raridade()),
+            armadura.get_encaixe().nome(),
+        ));
+        console.escrever_linha(&tr!("menu.separador"));
+    }
+
+    if armaduras.is_empty() {
+        console.escrever_linha(&tr!(vazio));
+    }
+}
+
+fn escrever_armas(armas: &[&Arma], vazio: &str, console: &mut Console) {
+    console.escrever_linha(&tr!("listagem.armas"));
+
+    for arma in armas {
+        console.escrever_linha(&tr!(
+            "listagem.arma",
+            arma.id(),
+            arma.get_nome(),
+            arma.get_dano(),
+            console.raridade(arma.get_raridade()),
+            arma.get_efeito().as_string(),
+        ));
+        console.escrever_linha(&tr!("menu.separador"));
+    }
+
+    if armas.is_empty() {
+        console.escrever_linha(&tr!(vazio));
+    }
+}
+
+fn escrever_consumiveis(consumiveis: &[&Consumivel], vazio: &str, console: &mut Console) {
+    console.escrever_linha(&tr!("listagem.consumiveis"));
+
+    for consumivel in consumiveis {
+        console.escrever_linha(&tr!(
+            "listagem.consumivel",
+            consumivel.id(),
+            consumivel.nome_com_quantidade(),
+            consumivel.get_efeito_vida(),
+            consumivel.get_descricao(),
+        ));
+        console.escrever_linha(&tr!("menu.separador"));
+    }
+
+    if consumiveis.is_empty() {
+        console.escrever_linha(&tr!(vazio));
+    }
+}
+
+/// Cria um personagem e o adiciona ao jogo, devolvendo o ID dele.
+/// No modo simulacao a vida, a forca e o equipamento sao escolhidos pelo jogador;
+/// no modo normal vem dos valores da classe e do kit inicial dela.
+pub fn criar_personagem(game: &mut Game, simulacao: bool, console: &mut Console) -> Result<Uuid, ErroRPG> {
+    console.escrever_linha(&tr!("personagem.criar"));
+    
+    let nome = console.ler_string_loop(&tr!("prompt.nome"))?;
+
+    let classe = ler_classe(console)?;
+
+    let id = if simulacao {
+        let vida = console.ler_u32_loop(&tr!("prompt.vida"))?;
+        let forca = console.ler_u32_loop(&tr!("prompt.forca"))?;
+        let armadura = armadura_simulacao(game, console)?;
+        let arma = arma_simulacao(game, classe.clone(), console)?;
+
+        game.adicionar_personagem(Personagem::new(nome, vida, forca, classe, armadura, arma))
+    }
+    else {
+        game.novo_personagem(nome, classe)
+    };
+
+    console.escrever_linha(&tr!("personagem.criado", game.personagem(id)?.nome));
+
+    Ok(id)
+}
+
+fn armadura_simulacao(game: &mut Game, console: &mut Console) -> Result<Armadura, ErroRPG> {
+    loop {
+        let opcao = console.ler_u32_loop(&tr!("simulacao.armadura"))?;
+
+        if opcao == 0 {
+            return criar_armadura(game, console);
+        }
+        else if opcao == 1 {
+            listar_todas_armaduras(game, console);
+            let id = console.ler_uuid_loop(&tr!("prompt.id"))?;
+            match game.item(id) {
+                Ok(ItemTipo::Armadura(armadura)) => return Ok(armadura.clone()),
+                _ => console.escrever_linha(&tr!("simulacao.armadura_inexistente")),
+            }
+        }
+        else {
+            console.escrever_linha(&tr!("menu.opcao_invalida"));
+        }
+    }
+}
+
+fn arma_simulacao(game: &mut Game, classe: Classe, console: &mut Console) -> Result<Arma, ErroRPG> {
+    loop {
+        let opcao = console.ler_u32_loop(&tr!("simulacao.arma"))?;
+
+        let arma = if opcao == 0 {
+            criar_arma(game, console)?
+        }
+        else if opcao == 1 {
+            listar_todas_armas(game, console);
+            let id = console.ler_uuid_loop(&tr!("prompt.id"))?;
+            match game.item(id) {
+                Ok(ItemTipo::Arma(arma)) => arma.clone(),
+                _ => {
+                    console.escrever_linha(&tr!("simulacao.arma_inexistente"));
+                    continue;
+                }
+            }
+        }
+        else {
+            console.escrever_linha(&tr!("menu.opcao_invalida"));
+            continue;
+        };
+
+        if *arma.get_classe() == classe {
+            return Ok(arma);
+        }
+
+        console.escrever_linha(&tr!("simulacao.arma_outra_classe"));
+    }
+}
+
+pub fn criar_armadura(game: &mut Game, console: &mut Console) -> Result<Armadura, ErroRPG> {
+    // nome defesa raridade
+    console.escrever_linha(&tr!("item.criar_armadura"));
+    
+    let nome = console.ler_string_loop(&tr!("prompt.nome"))?;
+    
+    let defesa = console.ler_u32_loop(&tr!("prompt.defesa"))?;
+    
+    let raridade = ler_raridade(console)?;
+
+    let peso = console.ler_u32_loop(&tr!("prompt.peso"))?;
+
+    let encaixe = ler_encaixe(&ENCAIXES_ARMADURA, "equipamento.menu_encaixe_armadura", console)?;
+
+    let mut armadura = Armadura::new(nome, defesa, raridade);
+    armadura.definir_peso(peso);
+    armadura.definir_encaixe(encaixe);
+
+    game.registrar_item(ItemTipo::Armadura(armadura.clone()));
+
+    Ok(armadura)
+}
+
+pub fn criar_arma(game: &mut Game, console: &mut Console) -> Result<Arma, ErroRPG> {
+    // nome defesa raridade
+    console.escrever_linha(&tr!("item.criar_arma"));
+    
+    let nome = console.ler_string_loop(&tr!("prompt.nome"))?;
+    
+    let dano = console.ler_u32_loop(&tr!("prompt.dano"))?;
+    
+    let classe = ler_classe(console)?;
+
+    let raridade = ler_raridade(console)?;
+
+    let efeito = ler_efeito(console)?;
+
+    let peso = console.ler_u32_loop(&tr!("prompt.peso"))?;
+
+    let encaixe = ler_encaixe(&ENCAIXES_ARMA, "equipamento.menu_encaixe_arma", console)?;
+
+    let mut arma = Arma::new(nome, dano, classe, raridade, efeito);
+    arma.definir_peso(peso);
+    arma.definir_encaixe(encaixe);
+
+    game.registrar_item(ItemTipo::Arma(arma.clone()));
+
+    Ok(arma)
+}
+
+/// Item aleatorio do gerador, registrado no catalogo.
+pub fn gerar_item(game: &mut Game, console: &mut Console) -> Result<ItemTipo, ErroRPG> {
+    console.escrever_linha(&tr!("item.gerar"));
+
+    let mut tipo = 0;
+    while !(1..=2).contains(&tipo) {
+        tipo = console.ler_u32_loop(&tr!("item.gerar_tipo"))?;
+    }
+
+    let classe = ler_classe(console)?;
+
+    let nivel = console.ler_u32_loop(&tr!("prompt.nivel"))?;
+
+    let raridade = ler_raridade(console)?;
+
+    let item = match tipo {
+        1 => ItemTipo::Arma(game.gerar_arma(&classe, nivel, Some(raridade))),
+        _ => ItemTipo::Armadura(game.gerar_armadura(&classe, nivel, Some(raridade))),
+    };
+
+    console.escrever_linha(&item.exibir_descricao());
+
+    Ok(item)
+}
+
+pub fn criar_consumivel(game: &mut Game, console: &mut Console) -> Result<Consumivel, ErroRPG> {
+    console.escrever_linha(&tr!("item.criar_consumivel"));
+
+    let nome = console.ler_string_loop(&tr!("prompt.nome"))?;
+
+    let efeito_vida = console.ler_i32_loop(&tr!("prompt.efeito_vida"))?;
+
+    let descricao = console.ler_string_loop(&tr!("prompt.descricao"))?;
+
+    let peso = console.ler_u32_loop(&tr!("prompt.peso"))?;
+
+    let mut consumivel = Consumivel::new(nome, efeito_vida, descricao);
+    consumivel.definir_peso(peso);
+
+    game.registrar_item(ItemTipo::Consumivel(consumivel.clone()));
+
+    Ok(consumivel)
+}
+
+
+
+fn ler_classe(console: &mut Console) -> Result<Classe, ErroRPG> {
+    let mut opcao = 3;
+    while opcao > 2 {
+        opcao = console.ler_u32_loop(&tr!("classe.menu"))?;
+        if opcao > 2 {
+            console.escrever_linha(&tr!("classe.opcao_invalida"));
+        }
+    }
+
+    match opcao {
+        0 => Ok(Classe::Guerreiro), 
+        1 => Ok(Classe::Mago), 
+        2 => Ok(Classe::Assassino), 
+        _ => Err(ErroRPG::ClasseInvalida)
+    }
+}
+
+fn ler_raridade(console: &mut Console) -> Result<Raridade, ErroRPG> {
+    loop {
+        let opcao = console.ler_u32_loop(&tr!("raridade.menu"))?;
+
+        match Raridade::TODAS.get(opcao as usize) {
+            Some(raridade) => return Ok(*raridade),
+            None => console.escrever_linha(&tr!("raridade.opcao_invalida")),
+        }
+    }
+}
+
+/// `menu` é a chave do menu com as opcoes, na ordem de `opcoes`.
+fn ler_encaixe(opcoes: &[Encaixe], menu: &str, console: &mut Console) -> Result<Encaixe, ErroRPG> {
+    loop {
+        let opcao = console.ler_u32_loop(&tr!(menu))?;
+
+        match opcoes.get(opcao as usize) {
+            Some(encaixe) => return Ok(*encaixe),
+            None => console.escrever_linha(&tr!("menu.opcao_invalida")),
+        }
+    }
+}
+
+fn ler_slot(console: &mut Console) -> Result<Slot, ErroRPG> {
+    loop {
+        let opcao = console.ler_u32_loop(&tr!("equipamento.menu_slot"))?;
+
+        match Slot::TODOS.get(opcao as usize) {
+            Some(slot) => return Ok(*slot),
+            None => console.escrever_linha(&tr!("menu.opcao_invalida")),
+        }
+    }
+}
+
+fn ler_efeito(console: &mut Console) -> Result<Efeito, ErroRPG> {
+    let mut opcao = 7;
+    while opcao > 6 {
+        opcao = console.ler_u32_loop(&tr!("efeito.menu"))?;
+        if opcao > 6 {
+            console.escrever_linha(&tr!("efeito.opcao_invalida"));
+        }
+    }
+
+    match opcao {
+        0 => Ok(Efeito::Fisico), 
+        1 => Ok(Efeito::Congelamento), 
+        2 => {
+            let dano = console.ler_u32_loop(&tr!("efeito.prompt.queimadura_dano"))?;
+            let num_rodadas = console.ler_u32_loop(&tr!("efeito.prompt.queimadura_rodadas"))?;
+            Ok(Efeito::Queimadura(dano, num_rodadas))
+        }, 
+        3 => {
+            let dano = console.ler_f32_loop(&tr!("efeito.prompt.veneno_dano"))?;
+            Ok(Efeito::Veneno(dano))
+        },
+        4 => {
+            let dano = console.ler_u32_loop(&tr!("efeito.prompt.eletricidade_dano"))?;
+            let probabilidade = console.ler_u32_loop(&tr!("efeito.prompt.eletricidade_chance"))?;
+            Ok(Efeito::Eletricidade(dano, probabilidade))
+        },
+        5 => {
+            let dano = console.ler_u32_loop(&tr!("efeito.prompt.sangramento_dano"))?;
+            let probabilidade = console.ler_u32_loop(&tr!("efeito.prompt.sangramento_chance"))?;
+            Ok(Efeito::Sangramento(dano, probabilidade))
+        },
+        6 => {
+            let reducao_dano = console.ler_u32_loop(&tr!("efeito.prompt.enfraquecimento"))?;
+            Ok(Efeito::Enfraquecimento(reducao_dano))
+        }
+        _ => Err(ErroRPG::EfeitoInvalido)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use p3::rpg_game::{armazenamento::ArmazenamentoMemoria, equipamento::Encaixe};
+
+    use super::*;
+
+    fn novo_jogo() -> Game {
+        Game::novo(Box::new(ArmazenamentoMemoria::new()))
+    }
+
+    /// Roda `fluxo` com `entrada` digitada pelo jogador, uma resposta por linha.
+    fn roteiro<T>(entrada: &str, fluxo: impl FnOnce(&mut Console) -> T) -> T {
+        let mut saida = Vec::new();
+        let mut console = Console::new(Cursor::new(entrada.as_bytes()), &mut saida);
+        fluxo(&mut console)
+    }
+
+    #[test]
+    fn novo_jogo_so_apaga_os_personagens_depois_de_confirmar() {
+        let mut game = novo_jogo();
+        game.novo_personagem("Ana".to_string(), Classe::Guerreiro);
+
+        // novo jogo, nao confirma, sai
+        roteiro("2\n0\n0\n", |console| iniciar_menu(&mut game, console)).unwrap();
+        assert_eq!(game.personagens().map(|p| p.nome.as_str()).collect::<Vec<_>>(), ["Ana"]);
+
+        // novo jogo, confirma, cria Bob, volta e sai
+        roteiro("2\n1\nBob\n1\n0\n0\n", |console| iniciar_menu(&mut game, console)).unwrap();
+        assert_eq!(game.personagens().map(|p| p.nome.as_str()).collect::<Vec<_>>(), ["Bob"]);
+    }
+
+    #[test]
+    fn criar_arma_registra_no_catalogo() {
+        let mut game = novo_jogo();
+
+        // nome, dano, classe, raridade, efeito, peso, mao
+        let arma = roteiro("Machado\n12\n0\n2\n0\n8\n1\n", |console| criar_arma(&mut game, console)).unwrap();
+
+        assert_eq!(arma.get_nome(), "Machado");
+        assert_eq!(arma.get_dano_base(), 12);
+        assert!(*arma.get_classe() == Classe::Guerreiro);
+        assert_eq!(arma.get_raridade(), Raridade::Raro);
+        assert_eq!(arma.get_peso(), 8);
+        assert_eq!(arma.get_encaixe(), Encaixe::MaoSecundaria);
+        assert!(matches!(game.item(arma.get_id()), Ok(ItemTipo::Arma(_))));
+    }
+
+    #[test]
+    fn listagem_fora_do_terminal_nao_tem_cores() {
+        let mut game = novo_jogo();
+        roteiro("Machado\n12\n0\n4\n0\n8\n0\n", |console| criar_arma(&mut game, console)).unwrap();
+
+        let mut saida = Vec::new();
+        listar_todas_armas(&game, &mut Console::new(Cursor::new(""), &mut saida));
+        let saida = String::from_utf8(saida).unwrap();
+
+        assert!(saida.contains("Machado"));
+        assert!(saida.contains(&Raridade::Lendario.nome()));
+        assert!(!saida.contains('\x1b'));
+    }
+
+    #[test]
+    fn entrada_invalida_pede_o_valor_de_novo() {
+        let mut game = novo_jogo();
+
+        let consumivel = roteiro("Pocao\nmuito\n25\nCura 25\n1\n", |console| criar_consumivel(&mut game, console)).unwrap();
+
+        assert_eq!(consumivel.get_efeito_vida(), 25);
+        assert_eq!(consumivel.get_descricao(), "Cura 25");
+    }
+
+    #[test]
+    fn entrada_encerrada_interrompe_o_fluxo() {
+        let mut game = novo_jogo();
+
+        let resultado = roteiro("Machado\n12\n", |console| criar_arma(&mut game, console));
+
+        assert!(matches!(resultado, Err(ErroRPG::EntradaEncerrada)));
+        assert_eq!(game.itens().count(), 0);
+    }
+
+    #[test]
+    fn criar_personagem_na_simulacao_usa_o_equipamento_digitado() {
+        let mut game = novo_jogo();
+
+        let entrada = concat!(
+            "Ana\n0\n80\n12\n",
+            // armadura nova: nome, defesa, raridade, peso, encaixe (peito)
+            "0\nCota\n9\n0\n10\n1\n",
+            // arma nova: nome, dano, classe, raridade, efeito, peso, mao
+            "0\nEspada\n7\n0\n0\n0\n5\n0\n",
+        );
+        let id = roteiro(entrada, |console| criar_personagem(&mut game, true, console)).unwrap();
+
+        let personagem = game.personagem(id).unwrap();
+        assert_eq!(personagem.nome, "Ana");
+        assert_eq!((personagem.vida, personagem.forca), (80, 12));
+        assert_eq!(personagem.get_armadura().map(Armadura::get_nome).as_deref(), Some("Cota"));
+        assert_eq!(personagem.get_arma().map(Arma::get_nome).as_deref(), Some("Espada"));
+        assert_eq!(personagem.defesa_total(), 9);
+    }
+
+    #[test]
+    fn simulacao_recusa_arma_de_outra_classe() {
+        let mut game = novo_jogo();
+
+        let entrada = concat!(
+            "Ana\n0\n80\n12\n",
+            "0\nCota\n9\n0\n10\n1\n",
+            // primeiro um cajado de mago, recusado; depois uma espada de guerreiro
+            "0\nCajado\n7\n1\n0\n0\n3\n0\n",
+            "0\nEspada\n7\n0\n0\n0\n5\n0\n",
+        );
+        let id = roteiro(entrada, |console| criar_personagem(&mut game, true, console)).unwrap();
+
+        assert_eq!(game.personagem(id).unwrap().get_arma().map(Arma::get_nome).as_deref(), Some("Espada"));
+    }
+
+    #[test]
+    fn criar_personagem_fora_da_simulacao_usa_o_kit_da_classe() {
+        let mut game = novo_jogo();
+
+        let id = roteiro("Bob\n1\n", |console| criar_personagem(&mut game, false, console)).unwrap();
+
+        let personagem = game.personagem(id).unwrap();
+        assert!(personagem.classe == Classe::Mago);
+        assert!(personagem.get_arma().is_some_and(|arma| *arma.get_classe() == Classe::Mago));
+        assert!(personagem.get_armadura().is_some());
+    }
+}
@@ -0,0 +1,6 @@
+//! Interface de terminal do jogo: subcomandos, leitura de entrada e os menus interativos.
+//! Fica no binario; outras interfaces usam o motor direto pela biblioteca `p3`.
+
+pub mod comandos;
+pub mod console;
+pub mod menus;
@@ -1,13 +1,93 @@
+use std::{error::Error, fmt, io};
+
 use uuid::Uuid;
 
+use crate::rpg_game::idioma::tr;
+
 #[derive(Debug)]
 pub enum ErroRPG {
     PersonagemNaoEncontrado(Uuid),
-    ItemNaoEncontrado(String), 
-    ErroPersistencia(String), 
-    EntradaInvalida(String), 
+    ItemNaoEncontrado(String),
+    ErroPersistencia(String),
+    EntradaInvalida(String),
+    EntradaEncerrada,
     ItemNaoUsavel(String),
     ArmaIncompativel(String),
     ClasseInvalida,
     EfeitoInvalido,
-}
\ No newline at end of file
+    BatalhaInvalida(String),
+    /// Pegar o item faria o personagem passar da capacidade de carga.
+    CapacidadeExcedida { personagem: String, item: String, peso: u32, capacidade: u32 },
+    /// O item nao encaixa no slot escolhido (um elmo na mao, um anel no peito...).
+    SlotIncompativel { item: String, slot: String },
+    /// Nao ha nada equipado no slot.
+    SlotVazio(String),
+    /// Falha de leitura ou escrita em disco (ou na entrada do console).
+    Io(io::Error),
+    /// Save, metadados ou arquivo de dados em JSON que nao pode ser lido ou gerado.
+    Json(serde_json::Error),
+    /// Save binario (CBOR) que nao pode ser lido ou gerado.
+    Binario(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for ErroRPG {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mensagem = match self {
+            ErroRPG::PersonagemNaoEncontrado(id) => tr!("erro.personagem_nao_encontrado", id),
+            ErroRPG::ItemNaoEncontrado(item) => tr!("erro.item_nao_encontrado", item),
+            ErroRPG::ErroPersistencia(motivo) => tr!("erro.persistencia", motivo),
+            ErroRPG::EntradaInvalida(motivo) => tr!("erro.entrada_invalida", motivo),
+            ErroRPG::EntradaEncerrada => tr!("erro.entrada_encerrada"),
+            ErroRPG::ItemNaoUsavel(motivo) => tr!("erro.item_nao_usavel", motivo),
+            ErroRPG::ArmaIncompativel(motivo) => tr!("erro.arma_incompativel", motivo),
+            ErroRPG::ClasseInvalida => tr!("erro.classe_invalida"),
+            ErroRPG::EfeitoInvalido => tr!("erro.efeito_invalido"),
+            ErroRPG::BatalhaInvalida(motivo) => tr!("erro.batalha_invalida", motivo),
+            ErroRPG::CapacidadeExcedida { personagem, item, peso, capacidade } => tr!(
+                "erro.capacidade_excedida", personagem, item, peso, capacidade
+            ),
+            ErroRPG::SlotIncompativel { item, slot } => tr!("erro.slot_incompativel", item, slot),
+            ErroRPG::SlotVazio(slot) => tr!("erro.slot_vazio", slot),
+            ErroRPG::Io(erro) => tr!("erro.io", erro),
+            ErroRPG::Json(erro) => tr!("erro.json", erro),
+            ErroRPG::Binario(erro) => tr!("erro.binario", erro),
+        };
+
+        f.write_str(&mensagem)
+    }
+}
+
+impl Error for ErroRPG {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ErroRPG::Io(erro) => Some(erro),
+            ErroRPG::Json(erro) => Some(erro),
+            ErroRPG::Binario(erro) => Some(erro.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ErroRPG {
+    fn from(erro: io::Error) -> Self {
+        ErroRPG::Io(erro)
+    }
+}
+
+impl From<serde_json::Error> for ErroRPG {
+    fn from(erro: serde_json::Error) -> Self {
+        ErroRPG::Json(erro)
+    }
+}
+
+impl From<ciborium::de::Error<io::Error>> for ErroRPG {
+    fn from(erro: ciborium::de::Error<io::Error>) -> Self {
+        ErroRPG::Binario(Box::new(erro))
+    }
+}
+
+impl From<ciborium::ser::Error<io::Error>> for ErroRPG {
+    fn from(erro: ciborium::ser::Error<io::Error>) -> Self {
+        ErroRPG::Binario(Box::new(erro))
+    }
+}
@@ -0,0 +1,25 @@
+//! Motor do RPG: estado do jogo, personagens, itens, batalhas e persistencia.
+//! Nao faz entrada e saida de terminal, entao pode ser usado por qualquer interface;
+//! o menu de console fica no binario `P3`.
+
+pub mod rpg_game;
+pub mod traits;
+pub mod errors;
+
+pub use errors::ErroRPG;
+pub use rpg_game::{
+    armazenamento::{Armazenamento, ArmazenamentoBinario, ArmazenamentoJson, ArmazenamentoMemoria},
+    batalha::ResultadoBatalha,
+    carga::RegraCarga,
+    equipamento::{Encaixe, Equipamento, Slot},
+    gerador::GeradorItens,
+    idioma::{definir_idioma, Idioma},
+    itens::{Arma, Armadura, Consumivel, Efeito, ItemTipo},
+    kits::KitsIniciais,
+    personagens::{Classe, Personagem},
+    raridade::Raridade,
+    saque::{Saque, TabelasSaque},
+    slots::GerenciadorSlots,
+    Game,
+};
+pub use traits::{Identificavel, ItemComportamento};
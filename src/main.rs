@@ -1,8 +1,113 @@
-mod rpg_game;
-pub mod traits;
-pub mod errors;
+mod cli;
 
+use std::path::Path;
+
+use p3::{errors::ErroRPG, rpg_game::{idioma::{self, tr, Idioma}, gerador::GeradorItens, kits::KitsIniciais, saque::TabelasSaque, Game}};
+
+use cli::{comandos::{self, Comando}, console::Console, menus};
+
+/// Arquivo opcional, no diretorio atual, que substitui os kits iniciais embutidos.
+const KITS_ARQUIVO: &str = "kits.json";
+
+/// Arquivo opcional, no diretorio atual, que substitui as tabelas de nomes dos itens gerados.
+const NOMES_ARQUIVO: &str = "nomes_itens.json";
+
+/// Arquivo opcional, no diretorio atual, que substitui as tabelas de saque embutidas.
+const SAQUES_ARQUIVO: &str = "saques.json";
+
+/// Idioma escolhido por `P3_IDIOMA` (ex.: `en`) ou, na falta dela, pelo `LANG` do sistema.
+/// Sem nenhuma das duas, ou com um idioma sem traducao, o jogo fica em portugues.
+fn idioma_inicial() -> Idioma {
+    ["P3_IDIOMA", "LANG"]
+        .iter()
+        .filter_map(|variavel| std::env::var(variavel).ok())
+        .find_map(|codigo| Idioma::de_codigo(&codigo))
+        .unwrap_or_default()
+}
 
 fn main() {
-    println!("Hello, world!");
+    idioma::definir_idioma(idioma_inicial());
+
+    let invocacao = match comandos::interpretar(std::env::args().skip(1)) {
+        Ok(invocacao) => invocacao,
+        Err(erro) => {
+            eprintln!("{}", erro);
+            eprintln!("{}", tr!("cli.uso"));
+            std::process::exit(2);
+        }
+    };
+
+    let mut console = Console::padrao();
+
+    match invocacao.comando {
+        Comando::Ajuda => console.escrever_linha(&tr!("cli.uso")),
+        Comando::Interativo => interativo(&invocacao.save, &mut console),
+        comando => {
+            if let Err(erro) = executar_comando(&invocacao.save, comando, &mut console) {
+                eprintln!("{}", tr!("main.erro", erro));
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Menus do jogo. O save é carregado com fallback para os backups e salvo ao sair.
+fn interativo(path: &Path, console: &mut Console) {
+    let mut game = match Game::carregar_com_backup(path) {
+        Ok(game) => game,
+        Err(erro) => {
+            eprintln!("{}", tr!("main.erro_carregar", erro));
+            std::process::exit(1);
+        }
+    };
+
+    carregar_dados(&mut game);
+
+    match menus::iniciar_menu(&mut game, console) {
+        Ok(()) | Err(ErroRPG::EntradaEncerrada) => {}
+        Err(erro) => eprintln!("{}", tr!("main.erro", erro)),
+    }
+
+    match game.salvar() {
+        Ok(()) => println!("{}", tr!("main.salvo", path.display())),
+        Err(erro) => eprintln!("{}", tr!("main.erro_salvar", erro)),
+    }
+}
+
+/// Subcomando nao interativo. Diferente dos menus, um save corrompido é erro em vez de
+/// cair para um backup, e o jogo so é salvo se o comando tiver mudado algo.
+fn executar_comando(path: &Path, comando: Comando, console: &mut Console) -> Result<(), ErroRPG> {
+    let mut game = Game::carregar(path)?;
+
+    carregar_dados(&mut game);
+
+    if comandos::executar(&mut game, comando, console)? {
+        game.salvar()?;
+    }
+
+    Ok(())
+}
+
+/// Arquivos de dados opcionais do diretorio atual. Um arquivo invalido é avisado e ignorado.
+fn carregar_dados(game: &mut Game) {
+    if Path::new(KITS_ARQUIVO).exists() {
+        match KitsIniciais::carregar(KITS_ARQUIVO) {
+            Ok(kits) => game.definir_kits(kits),
+            Err(erro) => eprintln!("{}", tr!("main.kits_invalidos", KITS_ARQUIVO, erro)),
+        }
+    }
+
+    if Path::new(NOMES_ARQUIVO).exists() {
+        match GeradorItens::carregar(NOMES_ARQUIVO) {
+            Ok(gerador) => game.definir_gerador(gerador),
+            Err(erro) => eprintln!("{}", tr!("main.nomes_invalidos", NOMES_ARQUIVO, erro)),
+        }
+    }
+
+    if Path::new(SAQUES_ARQUIVO).exists() {
+        match TabelasSaque::carregar(SAQUES_ARQUIVO) {
+            Ok(saques) => game.definir_saques(saques),
+            Err(erro) => eprintln!("{}", tr!("main.saques_invalidos", SAQUES_ARQUIVO, erro)),
+        }
+    }
 }
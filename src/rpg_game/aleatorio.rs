@@ -0,0 +1,60 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// Fonte de numeros aleatorios usada por todo o jogo (combate, saque, geracao de itens).
+/// Receber a fonte por parametro permite reproduzir qualquer resultado a partir de uma semente.
+pub trait FonteAleatoria {
+    fn proximo_u64(&mut self) -> u64;
+
+    /// Numero inteiro no intervalo fechado `[min, max]`.
+    fn intervalo(&mut self, min: u32, max: u32) -> u32 {
+        if min >= max {
+            return min;
+        }
+
+        let tamanho = (max - min) as u64 + 1;
+        min + (self.proximo_u64() % tamanho) as u32
+    }
+
+    /// Devolve `true` com `percentual`% de chance.
+    fn chance(&mut self, percentual: u32) -> bool {
+        self.intervalo(0, 99) < percentual
+    }
+}
+
+/// Gerador deterministico (SplitMix64). A mesma semente sempre gera a mesma sequencia,
+/// e o estado é salvo junto com o jogo para continuar a sequencia ao carregar.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RngSemente {
+    semente: u64,
+    estado: u64,
+}
+
+impl RngSemente {
+    pub fn new(semente: u64) -> Self {
+        RngSemente {
+            semente,
+            estado: semente,
+        }
+    }
+
+    /// Cria um gerador com uma semente nova, sorteada pelo sistema.
+    pub fn aleatoria() -> Self {
+        Self::new(Uuid::new_v4().as_u64_pair().0)
+    }
+
+    pub fn semente(&self) -> u64 {
+        self.semente
+    }
+}
+
+impl FonteAleatoria for RngSemente {
+    fn proximo_u64(&mut self) -> u64 {
+        self.estado = self.estado.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.estado;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
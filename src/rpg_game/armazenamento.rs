@@ -0,0 +1,133 @@
+use std::{fs::File, io::BufReader, path::{Path, PathBuf}, sync::{Arc, Mutex}};
+
+use serde_json::Value;
+
+use crate::{errors::ErroRPG, rpg_game::persistencia::{self, BACKUPS_PADRAO}};
+
+/// Onde e como o estado do jogo é guardado.
+/// O jogo entrega e recebe o save como documento JSON (`serde_json::Value`),
+/// assim as migracoes de versao funcionam igual para qualquer backend.
+pub trait Armazenamento {
+    /// Le o documento salvo. Devolve `Ok(None)` se ainda nao ha nada salvo.
+    fn ler(&self) -> Result<Option<Value>, ErroRPG>;
+
+    fn gravar(&mut self, documento: &Value) -> Result<(), ErroRPG>;
+}
+
+/// Backend padrao: arquivo JSON legivel, com gravacao atomica e backups rotativos.
+pub struct ArmazenamentoJson {
+    path: PathBuf,
+    backups: usize,
+}
+
+impl ArmazenamentoJson {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        ArmazenamentoJson {
+            path: path.as_ref().to_path_buf(),
+            backups: BACKUPS_PADRAO,
+        }
+    }
+
+    /// Define quantos backups manter ao gravar (0 desativa os backups).
+    pub fn com_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Armazenamento for ArmazenamentoJson {
+    fn ler(&self) -> Result<Option<Value>, ErroRPG> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&self.path)?;
+
+        let documento = serde_json::from_reader(BufReader::new(file))?;
+
+        Ok(Some(documento))
+    }
+
+    fn gravar(&mut self, documento: &Value) -> Result<(), ErroRPG> {
+        let conteudo = serde_json::to_vec_pretty(documento)?;
+
+        persistencia::gravar_atomico(&self.path, &conteudo, self.backups)
+    }
+}
+
+/// Backend binario compacto (CBOR), com a mesma gravacao atomica do backend JSON.
+pub struct ArmazenamentoBinario {
+    path: PathBuf,
+    backups: usize,
+}
+
+impl ArmazenamentoBinario {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        ArmazenamentoBinario {
+            path: path.as_ref().to_path_buf(),
+            backups: BACKUPS_PADRAO,
+        }
+    }
+
+    /// Define quantos backups manter ao gravar (0 desativa os backups).
+    pub fn com_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+}
+
+impl Armazenamento for ArmazenamentoBinario {
+    fn ler(&self) -> Result<Option<Value>, ErroRPG> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&self.path)?;
+
+        let documento = ciborium::from_reader(BufReader::new(file))?;
+
+        Ok(Some(documento))
+    }
+
+    fn gravar(&mut self, documento: &Value) -> Result<(), ErroRPG> {
+        let mut conteudo = Vec::new();
+
+        ciborium::into_writer(documento, &mut conteudo)?;
+
+        persistencia::gravar_atomico(&self.path, &conteudo, self.backups)
+    }
+}
+
+/// Backend em memoria, util para testes e para jogos que nao precisam ir para o disco.
+/// Clones compartilham o mesmo documento, entao quem criou o backend consegue
+/// inspecionar o que o jogo gravou.
+#[derive(Clone, Default)]
+pub struct ArmazenamentoMemoria {
+    documento: Arc<Mutex<Option<Value>>>,
+}
+
+impl ArmazenamentoMemoria {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ultimo documento gravado, se houver.
+    pub fn documento(&self) -> Option<Value> {
+        self.documento.lock().unwrap().clone()
+    }
+}
+
+impl Armazenamento for ArmazenamentoMemoria {
+    fn ler(&self) -> Result<Option<Value>, ErroRPG> {
+        Ok(self.documento())
+    }
+
+    fn gravar(&mut self, documento: &Value) -> Result<(), ErroRPG> {
+        *self.documento.lock().unwrap() = Some(documento.clone());
+        Ok(())
+    }
+}
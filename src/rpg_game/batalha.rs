@@ -0,0 +1,322 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{errors::ErroRPG, rpg_game::{aleatorio::FonteAleatoria, idioma::tr, itens::{Arma, Efeito}, personagens::Personagem, progressao::{experiencia_da_vitoria, EventoNivel}, saque::Saque, status::{dano_critico, StatusAtivo}}};
+
+/// Numero maximo de rodadas antes da batalha ser encerrada como empate.
+/// Evita laços infinitos quando nenhum dos lados consegue derrotar o outro.
+pub const MAX_RODADAS: u32 = 100;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum EventoCombate {
+    Ataque {
+        atacante: String,
+        defensor: String,
+        dano: u32,
+        vida_restante: u32,
+    },
+    Critico {
+        atacante: String,
+        dano_extra: u32,
+    },
+    EfeitoAplicado {
+        personagem: String,
+        efeito: String,
+    },
+    DanoEfeito {
+        personagem: String,
+        efeito: String,
+        dano: u32,
+        vida_restante: u32,
+    },
+    EfeitoExpirado {
+        personagem: String,
+        efeito: String,
+    },
+    Congelado {
+        personagem: String,
+    },
+    /// Perdeu a vez por carregar peso demais.
+    Lento {
+        personagem: String,
+    },
+    Choque {
+        personagem: String,
+        dano: u32,
+        vida_restante: u32,
+    },
+    Derrota {
+        personagem: String,
+    },
+}
+
+impl EventoCombate {
+    pub fn descrever(&self) -> String {
+        match self {
+            EventoCombate::Ataque { atacante, defensor, dano, vida_restante } => tr!(
+                "evento.ataque", atacante, defensor, dano, vida_restante
+            ),
+            EventoCombate::Critico { atacante, dano_extra } => tr!("evento.critico", atacante, dano_extra),
+            EventoCombate::EfeitoAplicado { personagem, efeito } => tr!("evento.efeito_aplicado", personagem, efeito),
+            EventoCombate::DanoEfeito { personagem, efeito, dano, vida_restante } => tr!(
+                "evento.dano_efeito", personagem, dano, efeito, vida_restante
+            ),
+            EventoCombate::EfeitoExpirado { personagem, efeito } => tr!("evento.efeito_expirado", efeito, personagem),
+            EventoCombate::Congelado { personagem } => tr!("evento.congelado", personagem),
+            EventoCombate::Lento { personagem } => tr!("evento.lento", personagem),
+            EventoCombate::Choque { personagem, dano, vida_restante } => tr!(
+                "evento.choque", personagem, dano, vida_restante
+            ),
+            EventoCombate::Derrota { personagem } => tr!("evento.derrota", personagem),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RegistroRodada {
+    pub rodada: u32,
+    pub eventos: Vec<EventoCombate>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ResultadoBatalha {
+    /// `None` quando a batalha atinge `MAX_RODADAS` sem um vencedor.
+    pub vencedor: Option<Uuid>,
+    pub perdedor: Option<Uuid>,
+    pub rodadas: u32,
+    pub log: Vec<RegistroRodada>,
+    /// Experiencia que o vencedor recebe pela vitoria.
+    pub experiencia: u32,
+    /// Niveis ganhos pelo vencedor, preenchido por quem aplica a experiencia.
+    #[serde(default)]
+    pub niveis: Vec<EventoNivel>,
+    /// Saque do vencedor, preenchido por quem entrega as recompensas.
+    #[serde(default)]
+    pub saque: Saque,
+}
+
+/// Batalha por turnos entre dois personagens.
+/// A cada rodada os efeitos ativos sao processados, o desafiante ataca primeiro
+/// e o oponente revida se ainda estiver vivo. A vida dos personagens é alterada diretamente.
+/// Toda a sorte da batalha vem de `rng`, entao a mesma semente reproduz a mesma batalha.
+pub struct Batalha<'a> {
+    desafiante: &'a mut Personagem,
+    oponente: &'a mut Personagem,
+    status_desafiante: StatusAtivo,
+    status_oponente: StatusAtivo,
+    rng: &'a mut dyn FonteAleatoria,
+    rodada: u32,
+    log: Vec<RegistroRodada>,
+}
+
+impl<'a> Batalha<'a> {
+    pub fn new(
+        desafiante: &'a mut Personagem,
+        oponente: &'a mut Personagem,
+        rng: &'a mut dyn FonteAleatoria,
+    ) -> Result<Self, ErroRPG> {
+        if !desafiante.esta_vivo() {
+            return Err(ErroRPG::BatalhaInvalida(tr!("batalha.ja_morto", desafiante.nome)));
+        }
+
+        if !oponente.esta_vivo() {
+            return Err(ErroRPG::BatalhaInvalida(tr!("batalha.ja_morto", oponente.nome)));
+        }
+
+        Ok(Batalha {
+            desafiante,
+            oponente,
+            status_desafiante: StatusAtivo::new(),
+            status_oponente: StatusAtivo::new(),
+            rng,
+            rodada: 0,
+            log: Vec::new(),
+        })
+    }
+
+    /// Liga as penalidades de carga: combatentes acima da capacidade causam menos dano
+    /// e podem perder a vez, proporcionalmente ao excesso de peso.
+    pub fn penalizar_sobrecarga(&mut self) {
+        self.status_desafiante.sobrecarga = self.desafiante.sobrecarga();
+        self.status_oponente.sobrecarga = self.oponente.sobrecarga();
+    }
+
+    /// Dano de um golpe: forca do atacante + dano das armas, menos a defesa do defensor
+    /// somada de todo o equipamento. Todo golpe causa pelo menos 1 de dano.
+    pub fn calcular_dano(atacante: &Personagem, defensor: &Personagem) -> u32 {
+        atacante.ataque()
+            .saturating_sub(defensor.defesa_total())
+            .max(1)
+    }
+
+    pub fn terminou(&self) -> bool {
+        !self.desafiante.esta_vivo() || !self.oponente.esta_vivo() || self.rodada >= MAX_RODADAS
+    }
+
+    /// Executa uma unica rodada e devolve o registro dela.
+    pub fn executar_rodada(&mut self) -> &RegistroRodada {
+        self.rodada += 1;
+
+        let mut eventos = Vec::new();
+
+        processar_efeitos(self.desafiante, &mut self.status_desafiante, &mut eventos);
+        processar_efeitos(self.oponente, &mut self.status_oponente, &mut eventos);
+
+        turno(self.desafiante, &mut self.status_desafiante, self.oponente, &mut self.status_oponente, self.rng, &mut eventos);
+        turno(self.oponente, &mut self.status_oponente, self.desafiante, &mut self.status_desafiante, self.rng, &mut eventos);
+
+        self.log.push(RegistroRodada { rodada: self.rodada, eventos });
+
+        self.log.last().unwrap()
+    }
+
+    /// Executa rodadas até um dos personagens morrer ou o limite de rodadas ser atingido.
+    pub fn executar(mut self) -> ResultadoBatalha {
+        while !self.terminou() {
+            self.executar_rodada();
+        }
+
+        let (vencedor, perdedor, experiencia) = if !self.oponente.esta_vivo() {
+            (Some(self.desafiante.id), Some(self.oponente.id), experiencia_da_vitoria(self.oponente))
+        } else if !self.desafiante.esta_vivo() {
+            (Some(self.oponente.id), Some(self.desafiante.id), experiencia_da_vitoria(self.desafiante))
+        } else {
+            (None, None, 0)
+        };
+
+        ResultadoBatalha {
+            vencedor,
+            perdedor,
+            rodadas: self.rodada,
+            log: self.log,
+            experiencia,
+            niveis: Vec::new(),
+            saque: Saque::default(),
+        }
+    }
+}
+
+fn processar_efeitos(personagem: &mut Personagem, status: &mut StatusAtivo, eventos: &mut Vec<EventoCombate>) {
+    if !personagem.esta_vivo() {
+        return;
+    }
+
+    status.tick(personagem, eventos);
+
+    if !personagem.esta_vivo() {
+        eventos.push(EventoCombate::Derrota { personagem: personagem.nome.clone() });
+    }
+}
+
+/// Turno de um combatente: congelamento e choque podem impedir o ataque.
+/// Se o golpe acertar, o efeito da arma da mao principal é aplicado ao defensor;
+/// sem arma, o golpe é um ataque fisico.
+fn turno(
+    atacante: &mut Personagem,
+    status_atacante: &mut StatusAtivo,
+    defensor: &mut Personagem,
+    status_defensor: &mut StatusAtivo,
+    rng: &mut dyn FonteAleatoria,
+    eventos: &mut Vec<EventoCombate>,
+) {
+    if !atacante.esta_vivo() || !defensor.esta_vivo() {
+        return;
+    }
+
+    if status_atacante.perde_a_vez() {
+        eventos.push(EventoCombate::Congelado { personagem: atacante.nome.clone() });
+        return;
+    }
+
+    if status_atacante.perde_a_vez_por_peso(rng) {
+        eventos.push(EventoCombate::Lento { personagem: atacante.nome.clone() });
+        return;
+    }
+
+    if let Some(dano) = status_atacante.sofre_choque(rng) {
+        atacante.receber_dano(dano);
+
+        eventos.push(EventoCombate::Choque {
+            personagem: atacante.nome.clone(),
+            dano,
+            vida_restante: atacante.vida,
+        });
+
+        if !atacante.esta_vivo() {
+            eventos.push(EventoCombate::Derrota { personagem: atacante.nome.clone() });
+        }
+        return;
+    }
+
+    let efeito = atacante.get_arma().map_or(Efeito::Fisico, Arma::get_efeito);
+    let mut dano = status_atacante.reduzir_dano(Batalha::calcular_dano(atacante, defensor));
+
+    if let Some(dano_extra) = dano_critico(&efeito, dano, rng) {
+        dano = dano.saturating_add(dano_extra);
+        eventos.push(EventoCombate::Critico { atacante: atacante.nome.clone(), dano_extra });
+    }
+
+    defensor.receber_dano(dano);
+
+    eventos.push(EventoCombate::Ataque {
+        atacante: atacante.nome.clone(),
+        defensor: defensor.nome.clone(),
+        dano,
+        vida_restante: defensor.vida,
+    });
+
+    if !defensor.esta_vivo() {
+        eventos.push(EventoCombate::Derrota { personagem: defensor.nome.clone() });
+        return;
+    }
+
+    status_defensor.aplicar(&efeito, defensor, eventos);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpg_game::{aleatorio::RngSemente, itens::Armadura, personagens::Classe, raridade::Raridade};
+
+    fn combatente(nome: &str, classe: Classe, efeito: Efeito) -> Personagem {
+        Personagem::new(
+            nome.to_string(), 0, 0, classe.clone(),
+            Armadura::new("Tunica".to_string(), 4, Raridade::Comum),
+            Arma::new("Arma".to_string(), 6, classe, Raridade::Comum, efeito),
+        )
+    }
+
+    /// Descricao de todos os eventos de uma batalha com a semente.
+    fn lutar(semente: u64) -> Vec<String> {
+        let mut a = combatente("Ana", Classe::Guerreiro, Efeito::Sangramento(50, 30));
+        let mut b = combatente("Bob", Classe::Mago, Efeito::Eletricidade(3, 20));
+        let mut rng = RngSemente::new(semente);
+
+        let resultado = Batalha::new(&mut a, &mut b, &mut rng).unwrap().executar();
+
+        resultado.log
+            .iter()
+            .flat_map(|registro| registro.eventos.iter().map(EventoCombate::descrever))
+            .collect()
+    }
+
+    #[test]
+    fn mesma_semente_gera_a_mesma_batalha() {
+        let log = lutar(42);
+
+        assert!(!log.is_empty());
+        assert_eq!(log, lutar(42));
+    }
+
+    #[test]
+    fn batalha_termina_com_um_derrotado_ou_no_limite() {
+        let mut a = combatente("Ana", Classe::Guerreiro, Efeito::Fisico);
+        let mut b = combatente("Bob", Classe::Assassino, Efeito::Fisico);
+        let mut rng = RngSemente::new(7);
+
+        let resultado = Batalha::new(&mut a, &mut b, &mut rng).unwrap().executar();
+
+        assert!(resultado.vencedor.is_some() || resultado.rodadas == MAX_RODADAS);
+        assert!(!a.esta_vivo() || !b.esta_vivo() || resultado.rodadas == MAX_RODADAS);
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+
+use crate::rpg_game::idioma::tr;
+
+/// Capacidade de carga de um personagem sem forca nenhuma.
+pub const CARGA_BASE: u32 = 20;
+
+/// Capacidade extra por ponto de forca.
+pub const CARGA_POR_FORCA: u32 = 2;
+
+/// Pesos usados quando o item nao informa o dele (saves antigos, itens criados sem peso).
+pub const PESO_ARMA: u32 = 5;
+pub const PESO_ARMADURA: u32 = 10;
+pub const PESO_CONSUMIVEL: u32 = 1;
+
+/// Reducao maxima do dano, em %, de um personagem acima da capacidade.
+pub const PENALIDADE_DANO_MAXIMA: u32 = 50;
+
+/// Chance maxima, em %, de um personagem acima da capacidade perder a vez.
+pub const PENALIDADE_LENTIDAO_MAXIMA: u32 = 30;
+
+pub(crate) fn peso_arma() -> u32 {
+    PESO_ARMA
+}
+
+pub(crate) fn peso_armadura() -> u32 {
+    PESO_ARMADURA
+}
+
+pub(crate) fn peso_consumivel() -> u32 {
+    PESO_CONSUMIVEL
+}
+
+/// O que acontece quando um personagem passa da capacidade de carga.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegraCarga {
+    /// Itens que passariam da capacidade sao recusados.
+    #[default]
+    RecusarItem,
+    /// O personagem carrega tudo, mas luta mais fraco e mais lento enquanto estiver acima da capacidade.
+    Penalidade,
+}
+
+impl RegraCarga {
+    pub fn nome(&self) -> String {
+        match self {
+            RegraCarga::RecusarItem => tr!("carga.recusar_item"),
+            RegraCarga::Penalidade => tr!("carga.penalidade"),
+        }
+    }
+}
+
+/// Reducao do dano, em %, para um personagem `sobrecarga`% acima da capacidade.
+pub fn penalidade_dano(sobrecarga: u32) -> u32 {
+    sobrecarga.min(PENALIDADE_DANO_MAXIMA)
+}
+
+/// Chance, em %, de um personagem `sobrecarga`% acima da capacidade perder a vez.
+pub fn chance_lentidao(sobrecarga: u32) -> u32 {
+    (sobrecarga / 2).min(PENALIDADE_LENTIDAO_MAXIMA)
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::{rpg_game::{idioma::tr, itens::{Arma, ItemTipo}}, traits::ItemComportamento};
+
+/// Lugar do corpo onde um item fica equipado.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Slot {
+    Cabeca,
+    Peito,
+    Pernas,
+    Maos,
+    MaoPrincipal,
+    MaoSecundaria,
+    Anel1,
+    Anel2,
+    Amuleto,
+}
+
+impl Slot {
+    /// Todos os slots, na ordem em que aparecem nas listagens e nos menus.
+    pub const TODOS: [Slot; 9] = [
+        Slot::Cabeca,
+        Slot::Peito,
+        Slot::Pernas,
+        Slot::Maos,
+        Slot::MaoPrincipal,
+        Slot::MaoSecundaria,
+        Slot::Anel1,
+        Slot::Anel2,
+        Slot::Amuleto,
+    ];
+
+    /// Identificador usado nos saves e na saida de texto da linha de comando.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Slot::Cabeca => "Cabeca",
+            Slot::Peito => "Peito",
+            Slot::Pernas => "Pernas",
+            Slot::Maos => "Maos",
+            Slot::MaoPrincipal => "MaoPrincipal",
+            Slot::MaoSecundaria => "MaoSecundaria",
+            Slot::Anel1 => "Anel1",
+            Slot::Anel2 => "Anel2",
+            Slot::Amuleto => "Amuleto",
+        }
+    }
+
+    /// Nome do slot no idioma atual, para mostrar ao jogador.
+    pub fn nome(&self) -> String {
+        match self {
+            Slot::Cabeca => tr!("equipamento.cabeca"),
+            Slot::Peito => tr!("equipamento.peito"),
+            Slot::Pernas => tr!("equipamento.pernas"),
+            Slot::Maos => tr!("equipamento.maos"),
+            Slot::MaoPrincipal => tr!("equipamento.mao_principal"),
+            Slot::MaoSecundaria => tr!("equipamento.mao_secundaria"),
+            Slot::Anel1 => tr!("equipamento.anel1"),
+            Slot::Anel2 => tr!("equipamento.anel2"),
+            Slot::Amuleto => tr!("equipamento.amuleto"),
+        }
+    }
+
+    /// Aceita o nome em portugues ou ingles, sem diferenciar maiusculas, hifens ou `_`
+    /// (`mao-principal`, `MaoPrincipal`, `main-hand`, ...).
+    pub fn de_texto(texto: &str) -> Option<Slot> {
+        let texto: String = texto
+            .to_lowercase()
+            .chars()
+            .filter(|c| *c != '-' && *c != '_' && *c != ' ')
+            .collect();
+
+        match texto.as_str() {
+            "cabeca" | "head" => Some(Slot::Cabeca),
+            "peito" | "chest" => Some(Slot::Peito),
+            "pernas" | "legs" => Some(Slot::Pernas),
+            "maos" | "hands" => Some(Slot::Maos),
+            "maoprincipal" | "mainhand" => Some(Slot::MaoPrincipal),
+            "maosecundaria" | "offhand" => Some(Slot::MaoSecundaria),
+            "anel1" | "ring1" => Some(Slot::Anel1),
+            "anel2" | "ring2" => Some(Slot::Anel2),
+            "amuleto" | "amulet" => Some(Slot::Amuleto),
+            _ => None,
+        }
+    }
+
+    /// Se um item que encaixa em `encaixe` pode ser equipado neste slot.
+    pub fn aceita(&self, encaixe: Encaixe) -> bool {
+        encaixe.slots().contains(self)
+    }
+}
+
+/// Onde um item pode ser equipado. Igual ao `Slot`, exceto pelos aneis,
+/// que servem em qualquer um dos dois dedos.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Encaixe {
+    Cabeca,
+    Peito,
+    Pernas,
+    Maos,
+    MaoPrincipal,
+    MaoSecundaria,
+    Anel,
+    Amuleto,
+}
+
+impl Encaixe {
+    /// Slots onde o item cabe, em ordem de preferencia.
+    pub fn slots(&self) -> &'static [Slot] {
+        match self {
+            Encaixe::Cabeca => &[Slot::Cabeca],
+            Encaixe::Peito => &[Slot::Peito],
+            Encaixe::Pernas => &[Slot::Pernas],
+            Encaixe::Maos => &[Slot::Maos],
+            Encaixe::MaoPrincipal => &[Slot::MaoPrincipal],
+            Encaixe::MaoSecundaria => &[Slot::MaoSecundaria],
+            Encaixe::Anel => &[Slot::Anel1, Slot::Anel2],
+            Encaixe::Amuleto => &[Slot::Amuleto],
+        }
+    }
+
+    pub fn nome(&self) -> String {
+        match self {
+            Encaixe::Anel => tr!("equipamento.anel"),
+            encaixe => encaixe.slots()[0].nome(),
+        }
+    }
+}
+
+pub(crate) fn encaixe_arma() -> Encaixe {
+    Encaixe::MaoPrincipal
+}
+
+pub(crate) fn encaixe_armadura() -> Encaixe {
+    Encaixe::Peito
+}
+
+/// Itens equipados em um personagem, por slot. Cada slot guarda no maximo um item.
+/// No save fica como um objeto `{"Peito": {"Armadura": {...}}, "MaoPrincipal": {"Arma": {...}}}`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Equipamento {
+    slots: BTreeMap<Slot, ItemTipo>,
+}
+
+impl Equipamento {
+    pub fn get(&self, slot: Slot) -> Option<&ItemTipo> {
+        self.slots.get(&slot)
+    }
+
+    /// Itens equipados, na ordem de `Slot::TODOS`.
+    pub fn itens(&self) -> impl Iterator<Item = (Slot, &ItemTipo)> {
+        self.slots.iter().map(|(slot, item)| (*slot, item))
+    }
+
+    pub(crate) fn itens_mut(&mut self) -> impl Iterator<Item = &mut ItemTipo> {
+        self.slots.values_mut()
+    }
+
+    /// Coloca o item no slot sem validar o encaixe, devolvendo o que estava la.
+    pub(crate) fn colocar(&mut self, slot: Slot, item: ItemTipo) -> Option<ItemTipo> {
+        self.slots.insert(slot, item)
+    }
+
+    pub(crate) fn tirar(&mut self, slot: Slot) -> Option<ItemTipo> {
+        self.slots.remove(&slot)
+    }
+
+    /// Slot onde o item com esse ID esta equipado, se estiver.
+    pub fn slot_do_item(&self, id: Uuid) -> Option<Slot> {
+        self.itens().find(|(_, item)| item.get_id() == id).map(|(slot, _)| slot)
+    }
+
+    /// Slot usado quando o jogador nao escolhe um: o primeiro livre entre os do encaixe
+    /// ou, com todos ocupados, o primeiro deles (o item que estava la é trocado).
+    pub fn slot_para(&self, encaixe: Encaixe) -> Slot {
+        let slots = encaixe.slots();
+
+        slots
+            .iter()
+            .copied()
+            .find(|slot| !self.slots.contains_key(slot))
+            .unwrap_or(slots[0])
+    }
+
+    /// Arma equipada no slot, se houver.
+    pub fn arma(&self, slot: Slot) -> Option<&Arma> {
+        match self.slots.get(&slot) {
+            Some(ItemTipo::Arma(arma)) => Some(arma),
+            _ => None,
+        }
+    }
+
+    /// Soma da defesa de todas as armaduras equipadas.
+    pub fn defesa(&self) -> u32 {
+        self.slots
+            .values()
+            .map(|item| match item {
+                ItemTipo::Armadura(armadura) => armadura.get_defesa(),
+                _ => 0,
+            })
+            .fold(0, u32::saturating_add)
+    }
+
+    /// Dano das armas: o da mao principal inteiro e metade do da mao secundaria.
+    pub fn dano(&self) -> u32 {
+        let principal = self.arma(Slot::MaoPrincipal).map_or(0, Arma::get_dano);
+        let secundaria = self.arma(Slot::MaoSecundaria).map_or(0, Arma::get_dano);
+
+        principal.saturating_add(secundaria / 2)
+    }
+
+    pub fn peso(&self) -> u32 {
+        self.slots.values().map(ItemTipo::peso_total).fold(0, u32::saturating_add)
+    }
+}
@@ -0,0 +1,187 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{errors::ErroRPG, rpg_game::{aleatorio::FonteAleatoria, idioma::tr, itens::{Arma, Armadura, Efeito}, personagens::Classe, raridade::Raridade}};
+
+/// Tabelas usadas quando nenhum arquivo de dados é informado.
+const NOMES_PADRAO: &str = include_str!("../../dados/nomes_itens.json");
+
+/// Tabelas de nomes dos itens gerados, no formato de `dados/nomes_itens.json`.
+/// O nome é composto por `<base> <qualificador> <sufixo>`: a base depende da classe,
+/// o qualificador da raridade e o sufixo do efeito da arma (armaduras e armas
+/// com efeito `Fisico` ficam sem sufixo).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TabelasNomes {
+    armas: HashMap<Classe, Vec<String>>,
+    armaduras: HashMap<Classe, Vec<String>>,
+    qualificadores: HashMap<Raridade, Vec<String>>,
+    /// Indexado pelo nome da variante do `Efeito` (`"Queimadura"`, `"Veneno"`, ...).
+    #[serde(default)]
+    sufixos: HashMap<String, Vec<String>>,
+}
+
+/// Tipo de item que o gerador sabe criar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TipoGerado {
+    Arma,
+    Armadura,
+}
+
+/// Gera armas e armaduras aleatorias para uma classe, nivel e raridade.
+/// Toda a sorte vem da `FonteAleatoria` recebida, entao a mesma semente gera os mesmos itens.
+#[derive(Clone)]
+pub struct GeradorItens {
+    nomes: TabelasNomes,
+}
+
+impl GeradorItens {
+    pub fn padrao() -> Self {
+        let nomes = serde_json::from_str(NOMES_PADRAO).expect("dados/nomes_itens.json embutido deve ser valido");
+        GeradorItens { nomes }
+    }
+
+    pub fn carregar(path: impl AsRef<Path>) -> Result<Self, ErroRPG> {
+        let conteudo = fs::read_to_string(path)?;
+
+        let nomes: TabelasNomes = serde_json::from_str(&conteudo)?;
+
+        for classe in [Classe::Guerreiro, Classe::Mago, Classe::Assassino] {
+            for tabela in [&nomes.armas, &nomes.armaduras] {
+                if tabela.get(&classe).is_none_or(Vec::is_empty) {
+                    return Err(ErroRPG::ErroPersistencia(tr!("gerador.tabela_vazia", classe.as_str())));
+                }
+            }
+        }
+
+        for raridade in Raridade::TODAS {
+            if nomes.qualificadores.get(&raridade).is_none_or(Vec::is_empty) {
+                return Err(ErroRPG::ErroPersistencia(tr!("gerador.tabela_vazia", format!("{raridade:?}"))));
+            }
+        }
+
+        Ok(GeradorItens { nomes })
+    }
+
+    /// Arma da classe com dano base sorteado numa faixa que cresce com o nivel e a raridade.
+    /// O multiplicador da raridade é aplicado por cima, em `Arma::get_dano`.
+    pub fn gerar_arma(&self, classe: &Classe, nivel: u32, raridade: Raridade, rng: &mut dyn FonteAleatoria) -> Arma {
+        let base = nivel.max(1).saturating_mul(2).saturating_add(5);
+        let dano = sortear_atributo(base, raridade, rng);
+        let efeito = sortear_efeito(nivel, raridade, rng);
+
+        let mut nome = self.compor_nome(&self.nomes.armas, classe, raridade, rng);
+        if let Some(sufixo) = self.nomes.sufixos.get(efeito.tipo()).and_then(|sufixos| escolher(sufixos, rng)) {
+            nome = format!("{nome} {sufixo}");
+        }
+
+        let mut arma = Arma::new(nome, dano, classe.clone(), raridade, efeito);
+        arma.definir_peso(peso_arma_da_classe(classe));
+        arma
+    }
+
+    /// Armadura com o estilo da classe e defesa base sorteada como em `gerar_arma`.
+    pub fn gerar_armadura(&self, classe: &Classe, nivel: u32, raridade: Raridade, rng: &mut dyn FonteAleatoria) -> Armadura {
+        let base = nivel.max(1).saturating_mul(3).saturating_add(6);
+        let defesa = sortear_atributo(base, raridade, rng);
+
+        let nome = self.compor_nome(&self.nomes.armaduras, classe, raridade, rng);
+
+        let mut armadura = Armadura::new(nome, defesa, raridade);
+        armadura.definir_peso(peso_armadura_da_classe(classe));
+        armadura
+    }
+
+    fn compor_nome(&self, bases: &HashMap<Classe, Vec<String>>, classe: &Classe, raridade: Raridade, rng: &mut dyn FonteAleatoria) -> String {
+        let base = bases.get(classe).and_then(|nomes| escolher(nomes, rng)).unwrap_or(classe.as_str());
+
+        match self.nomes.qualificadores.get(&raridade).and_then(|nomes| escolher(nomes, rng)) {
+            Some(qualificador) => format!("{base} {qualificador}"),
+            None => base.to_string(),
+        }
+    }
+}
+
+/// Armas pesadas de guerreiro, leves de mago e assassino.
+fn peso_arma_da_classe(classe: &Classe) -> u32 {
+    match classe {
+        Classe::Guerreiro => 8,
+        Classe::Mago => 4,
+        Classe::Assassino => 3,
+    }
+}
+
+fn peso_armadura_da_classe(classe: &Classe) -> u32 {
+    match classe {
+        Classe::Guerreiro => 15,
+        Classe::Mago => 4,
+        Classe::Assassino => 8,
+    }
+}
+
+fn escolher<'a>(opcoes: &'a [String], rng: &mut dyn FonteAleatoria) -> Option<&'a str> {
+    if opcoes.is_empty() {
+        return None;
+    }
+
+    Some(&opcoes[rng.intervalo(0, opcoes.len() as u32 - 1) as usize])
+}
+
+/// Sorteia um atributo em torno de `base`. Quanto mais rara, mais a faixa sobe:
+/// de 90%-110% da base (comum) ate 110%-150% (lendaria). A conta é feita em u64 para niveis
+/// muito altos nao estourarem; o resultado fica limitado a `u32::MAX`.
+fn sortear_atributo(base: u32, raridade: Raridade, rng: &mut dyn FonteAleatoria) -> u32 {
+    let tier = raridade as u64;
+    let percentual = |p: u64| (base as u64 * p / 100).min(u32::MAX as u64) as u32;
+
+    rng.intervalo(percentual(90 + 5 * tier), percentual(110 + 10 * tier)).max(1)
+}
+
+/// Efeito da arma. A chance de um efeito especial e a forca dele crescem com a raridade;
+/// o dano dos efeitos que causam dano tambem cresce com o nivel.
+fn sortear_efeito(nivel: u32, raridade: Raridade, rng: &mut dyn FonteAleatoria) -> Efeito {
+    let tier = raridade as u32;
+
+    if !rng.chance(25 + 15 * tier) {
+        return Efeito::Fisico;
+    }
+
+    match rng.intervalo(0, 5) {
+        0 => Efeito::Congelamento,
+        1 => Efeito::Queimadura(rng.intervalo(2 + tier, 4 + 2 * tier).saturating_add(nivel / 2), rng.intervalo(2, 3 + tier)),
+        2 => Efeito::Veneno(rng.intervalo(2 + tier, 4 + 2 * tier) as f32),
+        3 => Efeito::Eletricidade(rng.intervalo(2 + tier, 4 + 2 * tier).saturating_add(nivel / 2), rng.intervalo(10 + 5 * tier, 20 + 5 * tier)),
+        4 => Efeito::Sangramento(rng.intervalo(30 + 10 * tier, 50 + 15 * tier), rng.intervalo(15 + 5 * tier, 25 + 5 * tier)),
+        _ => Efeito::Enfraquecimento(rng.intervalo(10 + 5 * tier, 20 + 5 * tier)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rpg_game::aleatorio::RngSemente, traits::ItemComportamento};
+
+    #[test]
+    fn niveis_altos_nao_estouram() {
+        let gerador = GeradorItens::padrao();
+
+        for semente in 0..20 {
+            let mut rng = RngSemente::new(semente);
+
+            let arma = gerador.gerar_arma(&Classe::Guerreiro, u32::MAX, Raridade::Lendario, &mut rng);
+            let armadura = gerador.gerar_armadura(&Classe::Mago, 100_000_000, Raridade::Lendario, &mut rng);
+
+            assert!(arma.get_dano_base() >= u32::MAX / 2);
+            assert!(armadura.get_defesa_base() > 0);
+        }
+    }
+
+    #[test]
+    fn mesma_semente_gera_o_mesmo_item() {
+        let gerador = GeradorItens::padrao();
+
+        let gerar = || gerador.gerar_arma(&Classe::Assassino, 5, Raridade::Raro, &mut RngSemente::new(11));
+
+        assert_eq!(gerar().exibir_descricao(), gerar().exibir_descricao());
+    }
+}
@@ -0,0 +1,117 @@
+use std::{collections::HashMap, fmt::Display, sync::{OnceLock, RwLock}};
+
+/// Catalogos de mensagens embutidos, um por idioma, no formato `{"chave": "texto"}`.
+/// Os textos usam `{0}`, `{1}`, ... para os valores passados ao `tr!`.
+const CATALOGO_PT: &str = include_str!("../../dados/idiomas/pt.json");
+const CATALOGO_EN: &str = include_str!("../../dados/idiomas/en.json");
+
+/// Idioma de todo o texto mostrado ao jogador. O portugues é o idioma base:
+/// toda chave existe nele, e é para ele que as outras traducoes recorrem quando falta uma chave.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Idioma {
+    #[default]
+    Portugues,
+    Ingles,
+}
+
+impl Idioma {
+    pub fn codigo(&self) -> &str {
+        match self {
+            Idioma::Portugues => "pt",
+            Idioma::Ingles => "en",
+        }
+    }
+
+    /// Aceita o codigo do idioma sozinho ou no formato de locale (`en`, `en_US`, `pt-BR.UTF-8`).
+    pub fn de_codigo(codigo: &str) -> Option<Idioma> {
+        let base = codigo
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match base.as_str() {
+            "pt" => Some(Idioma::Portugues),
+            "en" => Some(Idioma::Ingles),
+            _ => None,
+        }
+    }
+
+    fn catalogo(&self) -> &'static HashMap<String, String> {
+        static PT: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+        let (celula, fonte) = match self {
+            Idioma::Portugues => (&PT, CATALOGO_PT),
+            Idioma::Ingles => (&EN, CATALOGO_EN),
+        };
+
+        celula.get_or_init(|| serde_json::from_str(fonte).expect("catalogo de idioma embutido deve ser valido"))
+    }
+}
+
+static IDIOMA_ATUAL: RwLock<Idioma> = RwLock::new(Idioma::Portugues);
+
+/// Troca o idioma de todas as mensagens. Deve ser chamado na inicializacao, antes dos menus.
+pub fn definir_idioma(idioma: Idioma) {
+    *IDIOMA_ATUAL.write().unwrap() = idioma;
+}
+
+pub fn idioma_atual() -> Idioma {
+    *IDIOMA_ATUAL.read().unwrap()
+}
+
+/// Texto da chave no idioma atual. Se a traducao nao existir, usa o portugues;
+/// se nem ele tiver a chave, devolve a propria chave para o erro ficar visivel.
+pub fn texto(chave: &str) -> String {
+    idioma_atual()
+        .catalogo()
+        .get(chave)
+        .or_else(|| Idioma::Portugues.catalogo().get(chave))
+        .cloned()
+        .unwrap_or_else(|| chave.to_string())
+}
+
+/// Como `texto`, trocando `{n}` pelo `n`-esimo argumento.
+pub fn formatar(chave: &str, argumentos: &[&dyn Display]) -> String {
+    let modelo = texto(chave);
+    let mut resultado = String::with_capacity(modelo.len());
+    let mut resto = modelo.as_str();
+
+    while let Some(inicio) = resto.find('{') {
+        resultado.push_str(&resto[..inicio]);
+        resto = &resto[inicio..];
+
+        let marcador = resto.find('}').and_then(|fim| {
+            let n = resto[1..fim].parse::<usize>().ok()?;
+            argumentos.get(n).map(|argumento| (fim, argumento))
+        });
+
+        match marcador {
+            Some((fim, argumento)) => {
+                resultado.push_str(&argumento.to_string());
+                resto = &resto[fim + 1..];
+            }
+            None => {
+                resultado.push('{');
+                resto = &resto[1..];
+            }
+        }
+    }
+
+    resultado.push_str(resto);
+    resultado
+}
+
+/// Mensagem traduzida: `tr!("chave")` ou `tr!("chave", valor0, valor1, ...)`.
+#[macro_export]
+macro_rules! tr {
+    ($chave:expr) => {
+        $crate::rpg_game::idioma::texto($chave)
+    };
+    ($chave:expr, $($argumento:expr),+ $(,)?) => {
+        $crate::rpg_game::idioma::formatar($chave, &[$(&$argumento),+])
+    };
+}
+
+pub use crate::tr;
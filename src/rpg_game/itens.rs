@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
-use crate::{errors::ErroRPG, rpg_game::personagens::{Classe, Personagem}, traits::{Identificavel, ItemComportamento}};
+use crate::{errors::ErroRPG, rpg_game::{carga::{peso_arma, peso_armadura, peso_consumivel, PESO_ARMA, PESO_ARMADURA, PESO_CONSUMIVEL}, equipamento::{encaixe_arma, encaixe_armadura, Encaixe}, idioma::tr, personagens::{Classe, Personagem}, raridade::Raridade}, traits::{Identificavel, ItemComportamento}};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub enum ItemTipo {
@@ -19,7 +19,12 @@ pub struct Arma {
     dano: u32,
     classe: Classe,
     efeito: Efeito,
-    raridade: String,
+    raridade: Raridade,
+    #[serde(default = "peso_arma")]
+    peso: u32,
+    /// Mao em que a arma é usada.
+    #[serde(default = "encaixe_arma")]
+    encaixe: Encaixe,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -27,7 +32,12 @@ pub struct Armadura {
     id: Uuid,
     nome: String,
     defesa: u32,
-    raridade: String,
+    raridade: Raridade,
+    #[serde(default = "peso_armadura")]
+    peso: u32,
+    /// Parte do corpo que a peca protege: elmos na cabeca, escudos na mao secundaria, etc.
+    #[serde(default = "encaixe_armadura")]
+    encaixe: Encaixe,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -36,9 +46,19 @@ pub struct Consumivel {
     nome: String,
     efeito_vida: i32,
     descricao: String,
+    /// Tamanho da pilha no inventario. No catalogo é sempre 1.
+    #[serde(default = "quantidade_padrao")]
+    quantidade: u32,
+    /// Peso de uma unidade; a pilha pesa `peso * quantidade`.
+    #[serde(default = "peso_consumivel")]
+    peso: u32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+pub(crate) fn quantidade_padrao() -> u32 {
+    1
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum Efeito {
     Fisico, //dano normal
     Congelamento, //ataque de congelamento adiciona 1 ponto de congelamento, com 3 pontos o oponente fica congelado e perde a vez
@@ -52,44 +72,80 @@ pub enum Efeito {
 impl Efeito {
     pub fn as_string(&self) -> String {
         match self {
-            Efeito::Fisico => "Fisico".to_string(),
+            Efeito::Fisico => tr!("efeito.fisico"),
 
-            Efeito::Congelamento => "Congelamento".to_string(),
+            Efeito::Congelamento => tr!("efeito.congelamento"),
             
-            Efeito::Queimadura(dano, rodadas) => format!("Queimadura; Dano: {dano}; Rodadas: {rodadas}", ),
+            Efeito::Queimadura(dano, rodadas) => tr!("efeito.queimadura_detalhes", dano, rodadas),
             
-            Efeito::Veneno(dano_percentual) => format!("Veneno; Dano por rodada: {}%", dano_percentual),
+            Efeito::Veneno(dano_percentual) => tr!("efeito.veneno_detalhes", dano_percentual),
             
-            Efeito::Eletricidade(dano_choque, probabilidade) => format!("Eletricidade; Dano do choque: {}; Probabilidade: {}%", dano_choque, probabilidade),
+            Efeito::Eletricidade(dano_choque, probabilidade) => tr!("efeito.eletricidade_detalhes", dano_choque, probabilidade),
             
-            Efeito::Sangramento(dano, probabilidade) => format!("Sangramento; Dano: {}%; Probabilidade: {}%", dano, probabilidade),
+            Efeito::Sangramento(dano, probabilidade) => tr!("efeito.sangramento_detalhes", dano, probabilidade),
             
-            Efeito::Enfraquecimento(reducao_dano) => format!("Enfraquecimento; Dano reduzido: {}%", reducao_dano),
+            Efeito::Enfraquecimento(reducao_dano) => tr!("efeito.enfraquecimento_detalhes", reducao_dano),
+        }
+    }
+
+    /// Nome da variante, sem os parametros. Usado como chave em arquivos de dados.
+    pub fn tipo(&self) -> &'static str {
+        match self {
+            Efeito::Fisico => "Fisico",
+            Efeito::Congelamento => "Congelamento",
+            Efeito::Queimadura(_, _) => "Queimadura",
+            Efeito::Veneno(_) => "Veneno",
+            Efeito::Eletricidade(_, _) => "Eletricidade",
+            Efeito::Sangramento(_, _) => "Sangramento",
+            Efeito::Enfraquecimento(_) => "Enfraquecimento",
         }
     }
 }
 
 impl Armadura {
-    pub fn new(nome: String, defesa: u32, raridade: String) -> Self {
+    pub fn new(nome: String, defesa: u32, raridade: Raridade) -> Self {
         Armadura {
             id: Uuid::new_v4(),
             nome,
             defesa,
             raridade,
+            peso: PESO_ARMADURA,
+            encaixe: Encaixe::Peito,
         }
     }
 
+    /// Defesa ja ampliada pela raridade.
     pub fn get_defesa(&self) -> u32 {
+        self.raridade.aplicar(self.defesa)
+    }
+
+    pub fn get_defesa_base(&self) -> u32 {
         self.defesa
     }
 
-    pub fn get_raridade(&self) -> String {
-        self.raridade.clone()
+    pub fn get_raridade(&self) -> Raridade {
+        self.raridade
+    }
+
+    pub fn get_peso(&self) -> u32 {
+        self.peso
+    }
+
+    pub fn definir_peso(&mut self, peso: u32) {
+        self.peso = peso;
+    }
+
+    pub fn get_encaixe(&self) -> Encaixe {
+        self.encaixe
+    }
+
+    pub fn definir_encaixe(&mut self, encaixe: Encaixe) {
+        self.encaixe = encaixe;
     }
 }
 
 impl Arma {
-    pub fn new(nome: String, dano: u32, classe: Classe, raridade: String, efeito: Efeito) -> Self {
+    pub fn new(nome: String, dano: u32, classe: Classe, raridade: Raridade, efeito: Efeito) -> Self {
         Arma {
             id: Uuid::new_v4(),
             nome,
@@ -97,20 +153,47 @@ impl Arma {
             classe,
             efeito,
             raridade,
+            peso: PESO_ARMA,
+            encaixe: Encaixe::MaoPrincipal,
         }
     }
 
+    /// Dano ja ampliado pela raridade.
     pub fn get_dano(&self) -> u32 {
+        self.raridade.aplicar(self.dano)
+    }
+
+    pub fn get_dano_base(&self) -> u32 {
         self.dano
     }
 
-    pub fn get_raridade(&self) -> String {
-        self.raridade.clone()
+    pub fn get_raridade(&self) -> Raridade {
+        self.raridade
     }
 
     pub fn get_efeito(&self) -> Efeito {
         self.efeito.clone()
     }
+
+    pub fn get_classe(&self) -> &Classe {
+        &self.classe
+    }
+
+    pub fn get_peso(&self) -> u32 {
+        self.peso
+    }
+
+    pub fn definir_peso(&mut self, peso: u32) {
+        self.peso = peso;
+    }
+
+    pub fn get_encaixe(&self) -> Encaixe {
+        self.encaixe
+    }
+
+    pub fn definir_encaixe(&mut self, encaixe: Encaixe) {
+        self.encaixe = encaixe;
+    }
 }
 
 
@@ -121,6 +204,8 @@ impl Consumivel {
             nome,
             efeito_vida,
             descricao,
+            quantidade: 1,
+            peso: PESO_CONSUMIVEL,
         }
     }
 
@@ -128,8 +213,130 @@ impl Consumivel {
         self.efeito_vida
     }
 
-    pub fn get_descricao(&self) -> i32 {
-        self.efeito_vida
+    pub fn get_descricao(&self) -> &str {
+        &self.descricao
+    }
+
+    pub fn get_quantidade(&self) -> u32 {
+        self.quantidade
+    }
+
+    pub fn definir_quantidade(&mut self, quantidade: u32) {
+        self.quantidade = quantidade;
+    }
+
+    pub fn get_peso(&self) -> u32 {
+        self.peso
+    }
+
+    pub fn definir_peso(&mut self, peso: u32) {
+        self.peso = peso;
+    }
+
+    /// Consumiveis com o mesmo nome, efeito, descricao e peso empilham juntos, mesmo com IDs diferentes.
+    pub fn mesma_definicao(&self, outro: &Consumivel) -> bool {
+        self.nome == outro.nome && self.efeito_vida == outro.efeito_vida && self.descricao == outro.descricao
+            && self.peso == outro.peso
+    }
+
+    /// Nome como aparece nas listagens, com o tamanho da pilha: `Pocao de Vida x5`.
+    pub fn nome_com_quantidade(&self) -> String {
+        if self.quantidade > 1 {
+            format!("{} x{}", self.nome, self.quantidade)
+        } else {
+            self.nome.clone()
+        }
+    }
+}
+
+impl ItemTipo {
+    /// Como `get_nome`, com o tamanho da pilha nos consumiveis.
+    pub fn nome_com_quantidade(&self) -> String {
+        match self {
+            ItemTipo::Consumivel(consumivel) => consumivel.nome_com_quantidade(),
+            item => item.get_nome(),
+        }
+    }
+
+    /// Se os dois itens sao o mesmo item, com os mesmos atributos, mesmo com IDs diferentes.
+    /// Consumiveis ignoram o tamanho da pilha.
+    pub fn mesma_definicao(&self, outro: &ItemTipo) -> bool {
+        match (self, outro) {
+            (ItemTipo::Arma(a), ItemTipo::Arma(b)) => {
+                a.nome == b.nome && a.dano == b.dano && a.classe == b.classe && a.efeito == b.efeito
+                    && a.raridade == b.raridade && a.peso == b.peso && a.encaixe == b.encaixe
+            }
+            (ItemTipo::Armadura(a), ItemTipo::Armadura(b)) => {
+                a.nome == b.nome && a.defesa == b.defesa && a.raridade == b.raridade
+                    && a.peso == b.peso && a.encaixe == b.encaixe
+            }
+            (ItemTipo::Consumivel(a), ItemTipo::Consumivel(b)) => a.mesma_definicao(b),
+            _ => false,
+        }
+    }
+
+    /// Copia do item com um ID novo, para guardar uma segunda copia de um item que nao empilha.
+    pub(crate) fn com_novo_id(&self) -> ItemTipo {
+        let mut copia = self.clone();
+
+        match &mut copia {
+            ItemTipo::Arma(arma) => arma.id = Uuid::new_v4(),
+            ItemTipo::Armadura(armadura) => armadura.id = Uuid::new_v4(),
+            ItemTipo::Consumivel(consumivel) => consumivel.id = Uuid::new_v4(),
+        }
+
+        copia
+    }
+
+    /// Onde o item pode ser equipado; consumiveis nao sao equipaveis.
+    pub fn encaixe(&self) -> Option<Encaixe> {
+        match self {
+            ItemTipo::Arma(arma) => Some(arma.encaixe),
+            ItemTipo::Armadura(armadura) => Some(armadura.encaixe),
+            ItemTipo::Consumivel(_) => None,
+        }
+    }
+
+    /// Peso do item no inventario; nos consumiveis, o da pilha inteira.
+    pub fn peso_total(&self) -> u32 {
+        match self {
+            ItemTipo::Arma(arma) => arma.peso,
+            ItemTipo::Armadura(armadura) => armadura.peso,
+            ItemTipo::Consumivel(consumivel) => consumivel.peso.saturating_mul(consumivel.quantidade),
+        }
+    }
+}
+
+/// Itens agrupados por tipo e ordenados por nome, como aparecem nas listagens.
+/// Serializa com os mesmos campos dos itens no save, para scripts e outras interfaces.
+#[derive(Serialize)]
+pub struct ListagemItens<'a> {
+    pub armaduras: Vec<&'a Armadura>,
+    pub armas: Vec<&'a Arma>,
+    pub consumiveis: Vec<&'a Consumivel>,
+}
+
+impl<'a> ListagemItens<'a> {
+    pub fn new(itens: impl IntoIterator<Item = &'a ItemTipo>) -> Self {
+        let mut listagem = ListagemItens {
+            armaduras: Vec::new(),
+            armas: Vec::new(),
+            consumiveis: Vec::new(),
+        };
+
+        for item in itens {
+            match item {
+                ItemTipo::Armadura(armadura) => listagem.armaduras.push(armadura),
+                ItemTipo::Arma(arma) => listagem.armas.push(arma),
+                ItemTipo::Consumivel(consumivel) => listagem.consumiveis.push(consumivel),
+            }
+        }
+
+        listagem.armaduras.sort_by(|a, b| a.nome.cmp(&b.nome));
+        listagem.armas.sort_by(|a, b| a.nome.cmp(&b.nome));
+        listagem.consumiveis.sort_by(|a, b| a.nome.cmp(&b.nome));
+
+        listagem
     }
 }
 //implementacao de ItemComportamento
@@ -148,28 +355,13 @@ impl ItemComportamento for Armadura {
     }
 
     fn exibir_descricao(&self) -> String {
-        format!(
-            "Nome: {} \nDefesa: {} \nRaridade: {}", self.nome, self.defesa, self.raridade,
-        )
+        tr!("item.descricao_armadura", self.nome, self.get_defesa(), self.raridade.nome(), self.peso, self.encaixe.nome())
     }
     
 
+    /// Equipa a armadura no primeiro slot livre do encaixe dela.
     fn usar(&self, personagem: &mut Personagem) -> Result<bool, ErroRPG> {
-        if personagem.inventario.contains_key(&self.id) {
-            return Err(ErroRPG::ItemNaoEncontrado(self.id));
-        }
-
-        if personagem.armadura.get_nome() == self.get_nome() {
-            return Err(ErroRPG::ItemNaoUsavel("Esse item ja esta sendo usado".to_string()));
-        }
-
-        if personagem.armadura.get_nome() != "Nenhuma" {
-            personagem.inventario.insert(personagem.armadura.get_id(), ItemTipo::Armadura(personagem.armadura.clone()));
-        }
-
-        personagem.armadura = self.clone();
-        personagem.defesa = self.defesa;
-
+        personagem.equipar(self.id, None)?;
         Ok(true)
     }
 }
@@ -188,31 +380,13 @@ impl ItemComportamento for Arma {
     }
 
     fn exibir_descricao(&self) -> String {
-        format!(
-            "Nome: {} \nDano: {} \nClasse: {} \nEfeito: {} \nRaridade: {}", self.nome, self.dano, self.classe.as_str(), self.efeito.as_string(), self.raridade
-        )
+        tr!("item.descricao_arma", self.nome, self.get_dano(), self.classe.nome(), self.efeito.as_string(), self.raridade.nome(), self.peso, self.encaixe.nome())
     }
     
 
+    /// Equipa a arma na mao dela, se for da classe do personagem.
     fn usar(&self, personagem: &mut Personagem) -> Result<bool, ErroRPG> {
-        if personagem.inventario.contains_key(&self.id) {
-            return Err(ErroRPG::ItemNaoEncontrado(self.id));
-        }
-
-        if personagem.arma.get_nome() == self.get_nome() {
-            return Err(ErroRPG::ItemNaoUsavel("Esse item ja esta sendo usado".to_string()));
-        }
-
-        if personagem.arma.classe.as_str() != self.classe.as_str() {
-            return Err(ErroRPG::ArmaIncompativel("A classe da arma é diferente da classe do personagem!".to_string()));
-        }
-
-        if personagem.arma.get_nome() != "Nenhuma" {
-            personagem.inventario.insert(personagem.arma.get_id(), ItemTipo::Arma(personagem.arma.clone()));
-        }
-
-        personagem.arma = self.clone();
-
+        personagem.equipar(self.id, None)?;
         Ok(true)
     }
 }
@@ -231,23 +405,27 @@ impl ItemComportamento for Consumivel {
     }
 
     fn exibir_descricao(&self) -> String {
-        format!(
-            "Nome: {} \nEfeito: {} \nDescricao: {}", self.nome, self.efeito_vida, self.descricao
-        )
+        tr!("item.descricao_consumivel", self.nome, self.efeito_vida, self.descricao, self.peso)
     }
     
     fn usar(&self, personagem: &mut Personagem) -> Result<bool, ErroRPG> {
-        if personagem.inventario.contains_key(&self.id) {
-            return Err(ErroRPG::ItemNaoEncontrado(self.id));
+        if !personagem.inventario.contains_key(&self.id) {
+            return Err(ErroRPG::ItemNaoEncontrado(self.id.to_string()));
         }
 
-        if personagem.vida <= 0 {
-            if self.nome != "Revive" {
-                return Err(ErroRPG::ItemNaoUsavel("O personagem ja esta morto".to_string()));
-            }
-            personagem.vida = self.efeito_vida as u32;
+        if !personagem.esta_vivo() && (self.nome != "Revive" || self.efeito_vida <= 0) {
+            return Err(ErroRPG::ItemNaoUsavel(tr!("item.personagem_morto")));
+        }
+
+        if self.efeito_vida >= 0 {
+            personagem.curar(self.efeito_vida as u32);
         } else {
-            personagem.vida += self.efeito_vida as u32;
+            personagem.receber_dano(self.efeito_vida.unsigned_abs());
+        }
+
+        match personagem.inventario.get_mut(&self.id) {
+            Some(ItemTipo::Consumivel(pilha)) if pilha.quantidade > 1 => pilha.quantidade -= 1,
+            _ => { personagem.inventario.remove(&self.id); }
         }
 
         Ok(true)
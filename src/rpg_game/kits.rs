@@ -0,0 +1,155 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{errors::ErroRPG, rpg_game::{equipamento::Encaixe, idioma::tr, itens::{quantidade_padrao, Arma, Armadura, Consumivel, Efeito}, personagens::Classe, raridade::Raridade}};
+
+/// Kits usados quando nenhum arquivo de dados é informado.
+const KITS_PADRAO: &str = include_str!("../../dados/kits.json");
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DefinicaoArma {
+    pub nome: String,
+    pub dano: u32,
+    pub raridade: Raridade,
+    pub efeito: Efeito,
+    /// Sem peso, o item usa o peso padrao do tipo.
+    #[serde(default)]
+    pub peso: Option<u32>,
+    /// Sem encaixe, a arma vai na mao principal.
+    #[serde(default)]
+    pub encaixe: Option<Encaixe>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DefinicaoArmadura {
+    pub nome: String,
+    pub defesa: u32,
+    pub raridade: Raridade,
+    /// Sem peso, o item usa o peso padrao do tipo.
+    #[serde(default)]
+    pub peso: Option<u32>,
+    /// Sem encaixe, a armadura vai no peito.
+    #[serde(default)]
+    pub encaixe: Option<Encaixe>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DefinicaoConsumivel {
+    pub nome: String,
+    pub efeito_vida: i32,
+    pub descricao: String,
+    #[serde(default = "quantidade_padrao")]
+    pub quantidade: u32,
+    /// Sem peso, o item usa o peso padrao do tipo.
+    #[serde(default)]
+    pub peso: Option<u32>,
+}
+
+impl DefinicaoArma {
+    /// Arma nova com um ID proprio. A classe vem de quem recebe a arma.
+    pub fn criar(&self, classe: Classe) -> Arma {
+        let mut arma = Arma::new(self.nome.clone(), self.dano, classe, self.raridade, self.efeito.clone());
+        if let Some(peso) = self.peso {
+            arma.definir_peso(peso);
+        }
+        if let Some(encaixe) = self.encaixe {
+            arma.definir_encaixe(encaixe);
+        }
+        arma
+    }
+}
+
+impl DefinicaoArmadura {
+    pub fn criar(&self) -> Armadura {
+        let mut armadura = Armadura::new(self.nome.clone(), self.defesa, self.raridade);
+        if let Some(peso) = self.peso {
+            armadura.definir_peso(peso);
+        }
+        if let Some(encaixe) = self.encaixe {
+            armadura.definir_encaixe(encaixe);
+        }
+        armadura
+    }
+}
+
+impl DefinicaoConsumivel {
+    /// Consumivel novo, com quantidade 1, como fica no catalogo.
+    pub fn criar(&self) -> Consumivel {
+        let mut consumivel = Consumivel::new(self.nome.clone(), self.efeito_vida, self.descricao.clone());
+        if let Some(peso) = self.peso {
+            consumivel.definir_peso(peso);
+        }
+        consumivel
+    }
+}
+
+/// Equipamento inicial de uma classe. A arma sempre é da classe do kit.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KitInicial {
+    pub arma: DefinicaoArma,
+    pub armadura: DefinicaoArmadura,
+    #[serde(default)]
+    pub consumiveis: Vec<DefinicaoConsumivel>,
+    #[serde(default)]
+    pub ouro: u32,
+}
+
+/// Kits iniciais de todas as classes, lidos de um arquivo JSON no formato de `dados/kits.json`.
+/// Um arquivo sem o kit de alguma classe é recusado ao ser lido, entao `kit` sempre encontra um.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "HashMap<Classe, KitInicial>")]
+pub struct KitsIniciais {
+    #[serde(flatten)]
+    kits: HashMap<Classe, KitInicial>,
+}
+
+impl TryFrom<HashMap<Classe, KitInicial>> for KitsIniciais {
+    type Error = ErroRPG;
+
+    fn try_from(kits: HashMap<Classe, KitInicial>) -> Result<Self, ErroRPG> {
+        for classe in [Classe::Guerreiro, Classe::Mago, Classe::Assassino] {
+            if !kits.contains_key(&classe) {
+                return Err(ErroRPG::ErroPersistencia(tr!("kits.classe_faltando", classe.as_str())));
+            }
+        }
+
+        Ok(KitsIniciais { kits })
+    }
+}
+
+impl KitsIniciais {
+    pub fn padrao() -> Self {
+        serde_json::from_str(KITS_PADRAO).expect("dados/kits.json embutido deve ser valido")
+    }
+
+    pub fn carregar(path: impl AsRef<Path>) -> Result<Self, ErroRPG> {
+        let conteudo = fs::read_to_string(path)?;
+
+        Ok(serde_json::from_str(&conteudo)?)
+    }
+
+    pub fn kit(&self, classe: &Classe) -> &KitInicial {
+        &self.kits[classe]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kits_sem_alguma_classe_sao_recusados() {
+        let kit = r#"{
+            "arma": { "nome": "Bastao", "dano": 5, "raridade": "Comum", "efeito": "Fisico" },
+            "armadura": { "nome": "Tunica", "defesa": 2, "raridade": "Comum" }
+        }"#;
+
+        let incompleto = format!(r#"{{ "Guerreiro": {kit}, "Mago": {kit} }}"#);
+        let completo = format!(r#"{{ "Guerreiro": {kit}, "Mago": {kit}, "Assassino": {kit} }}"#);
+
+        let erro = serde_json::from_str::<KitsIniciais>(&incompleto).err().unwrap();
+        assert!(erro.to_string().contains("Assassino"));
+        assert_eq!(serde_json::from_str::<KitsIniciais>(&completo).unwrap().kit(&Classe::Assassino).arma.nome, "Bastao");
+    }
+}
@@ -1,354 +1,540 @@
-use std::{collections::HashMap, fs::File, io::{self, BufReader, BufWriter, Write}, path::Path};
+use std::{collections::HashMap, fs, path::Path, time::Instant};
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 
-use crate::{errors::ErroRPG, rpg_game::{itens::{Arma, Armadura, Consumivel, Efeito, ItemTipo}, personagens::{Classe, Personagem}}, traits::{Identificavel, ItemComportamento}};
+use crate::{errors::ErroRPG, rpg_game::{aleatorio::RngSemente, idioma::tr, kits::KitsIniciais, gerador::{GeradorItens, TipoGerado}, raridade::Raridade, saque::{ItemSaque, Saque, TabelaSaque, TabelasSaque}, carga::RegraCarga, equipamento::Slot, armazenamento::{Armazenamento, ArmazenamentoJson, ArmazenamentoMemoria}, batalha::{Batalha, ResultadoBatalha}, persistencia::VERSAO_SAVE, itens::{Arma, Armadura, Consumivel, ItemTipo, ListagemItens}, personagens::{Classe, Personagem}, progressao::{CurvaExperiencia, EventoNivel}}, traits::ItemComportamento};
 
+pub mod aleatorio;
 pub mod personagens;
 pub mod itens;
+pub mod batalha;
+pub mod status;
+pub mod progressao;
+pub mod persistencia;
+pub mod armazenamento;
+pub mod slots;
+pub mod kits;
+pub mod idioma;
+pub mod raridade;
+pub mod gerador;
+pub mod saque;
+pub mod carga;
+pub mod equipamento;
 
 
 #[derive(Serialize, Deserialize)]
 pub struct Game {
+    versao: u32,
     personagens: HashMap<Uuid, Personagem>,
-    #[serde(skip)]
+    /// Catalogo global de itens. Os itens nos inventarios sao copias das entradas daqui, com o mesmo ID.
+    #[serde(default)]
     itens: HashMap<Uuid, ItemTipo>,
-    persistencia_path: std::path::PathBuf,    
+    /// Gerador usado em toda a sorte do jogo; o estado é salvo para que a partida seja reproduzivel.
+    #[serde(default = "RngSemente::aleatoria")]
+    rng: RngSemente,
+    #[serde(default)]
+    curva_experiencia: CurvaExperiencia,
+    #[serde(default)]
+    regra_carga: RegraCarga,
+    /// Tempo de jogo acumulado ate o ultimo save, em segundos.
+    #[serde(default)]
+    tempo_jogo: u64,
+    #[serde(skip, default = "Instant::now")]
+    inicio_sessao: Instant,
+    /// Para onde o jogo vai em `salvar`.
+    #[serde(skip, default = "armazenamento_padrao")]
+    armazenamento: Box<dyn Armazenamento>,
+    /// Equipamento inicial de cada classe, vindo de um arquivo de dados.
+    #[serde(skip, default = "KitsIniciais::padrao")]
+    kits: KitsIniciais,
+    /// Tabelas de nomes dos itens gerados aleatoriamente.
+    #[serde(skip, default = "GeradorItens::padrao")]
+    gerador: GeradorItens,
+    /// Tabelas de saque de inimigos e baus.
+    #[serde(skip, default = "TabelasSaque::padrao")]
+    saques: TabelasSaque,
+}
+
+fn armazenamento_padrao() -> Box<dyn Armazenamento> {
+    Box::new(ArmazenamentoMemoria::new())
 }
 
 impl Game {
+    /// Jogo vazio que sera salvo em `armazenamento`.
+    pub fn novo(armazenamento: Box<dyn Armazenamento>) -> Self {
+        Game {
+            versao: VERSAO_SAVE,
+            personagens: HashMap::new(),
+            itens: HashMap::new(),
+            rng: RngSemente::aleatoria(),
+            curva_experiencia: CurvaExperiencia::default(),
+            regra_carga: RegraCarga::default(),
+            tempo_jogo: 0,
+            inicio_sessao: Instant::now(),
+            armazenamento,
+            kits: KitsIniciais::padrao(),
+            gerador: GeradorItens::padrao(),
+            saques: TabelasSaque::padrao(),
+        }
+    }
+
     /// Carrega os dados do jogo de um arquivo JSON.
     /// Se o arquivo não existir, retorna um novo Game vazio.
+    /// Saves de versoes anteriores sao migrados para o formato atual antes de serem lidos.
     pub fn carregar(path: impl AsRef<Path>) -> Result<Self, ErroRPG> {
+        Self::carregar_de(Box::new(ArmazenamentoJson::new(path)))
+    }
+
+    /// Carrega o jogo de qualquer backend; se nao houver nada salvo, retorna um novo Game vazio.
+    /// O jogo carregado continua salvando no mesmo backend.
+    pub fn carregar_de(armazenamento: Box<dyn Armazenamento>) -> Result<Self, ErroRPG> {
+        match armazenamento.ler()? {
+            Some(documento) => Self::de_documento(documento, armazenamento),
+            None => Ok(Self::novo(armazenamento)),
+        }
+    }
+
+    /// Carrega o save e, se ele estiver corrompido, tenta os backups do mais recente ao mais antigo.
+    /// O jogo carregado continua apontando para o save principal, que sera reescrito no proximo `salvar`.
+    /// Um save de uma versao mais nova do jogo nao cai para os backups: o erro volta para quem chamou,
+    /// para que o progresso dele nao seja sobrescrito por um backup antigo.
+    pub fn carregar_com_backup(path: impl AsRef<Path>) -> Result<Self, ErroRPG> {
         let path = path.as_ref();
-        
-        if !path.exists() {
-            return Ok(Self {
-                personagens: HashMap::new(),
-                persistencia_path: path.to_path_buf(),
-                itens: HashMap::new()
-            });
+        let armazenamento = ArmazenamentoJson::new(path);
+
+        let erro = match armazenamento.ler() {
+            Ok(None) => return Ok(Self::novo(Box::new(armazenamento))),
+            Ok(Some(documento)) => {
+                persistencia::verificar_versao(&documento)?;
+
+                match Self::de_documento(documento, Box::new(armazenamento)) {
+                    Ok(game) => return Ok(game),
+                    Err(erro) => erro,
+                }
+            }
+            Err(erro) => erro,
+        };
+
+        let backups = (1..)
+            .map(|n| persistencia::caminho_backup(path, n))
+            .take_while(|backup| backup.exists());
+
+        for backup in backups {
+            if let Ok(Some(documento)) = ArmazenamentoJson::new(&backup).ler()
+                && let Ok(game) = Self::de_documento(documento, Box::new(ArmazenamentoJson::new(path)))
+            {
+                return Ok(game);
+            }
         }
 
-        let file = File::open(path)
-            .map_err(|e| ErroRPG::ErroPersistencia(format!("Erro ao abrir arquivo: {}", e)))?;
-        
-        let reader = BufReader::new(file);
-        
-        let mut game: Game = serde_json::from_reader(reader)
-            .map_err(|e| ErroRPG::ErroPersistencia(format!("Erro ao deserializar JSON: {}", e)))?;
-        
-        game.persistencia_path = path.to_path_buf();
-        
+        Err(erro)
+    }
+
+    /// Substitui o save principal pelo backup `n` e carrega o jogo a partir dele.
+    pub fn restaurar_backup(path: impl AsRef<Path>, n: usize) -> Result<Self, ErroRPG> {
+        let path = path.as_ref();
+        let backup = persistencia::caminho_backup(path, n);
+
+        let documento = ArmazenamentoJson::new(&backup)
+            .ler()?
+            .ok_or_else(|| ErroRPG::ErroPersistencia(tr!("save.backup_inexistente", n)))?;
+
+        let game = Self::de_documento(documento, Box::new(ArmazenamentoJson::new(path)))?;
+
+        fs::copy(&backup, path)?;
+
         Ok(game)
     }
 
-    /// Salva o estado atual do jogo em um arquivo JSON.
-    /// Cria o arquivo se não existir ou sobrescreve se já existir.
-    pub fn salvar(&self) -> Result<(), ErroRPG> {
-        let file = File::create(&self.persistencia_path)
-            .map_err(|e| ErroRPG::ErroPersistencia(format!("Erro ao criar arquivo: {}", e)))?;
-        
-        let writer = BufWriter::new(file);
+    /// Migra o documento para a versao atual e monta o jogo a partir dele.
+    fn de_documento(mut documento: serde_json::Value, armazenamento: Box<dyn Armazenamento>) -> Result<Self, ErroRPG> {
+        persistencia::migrar(&mut documento)?;
+
+        let mut game: Game = serde_json::from_value(documento)?;
         
-        serde_json::to_writer_pretty(writer, self)
-            .map_err(|e| ErroRPG::ErroPersistencia(format!("Erro ao serializar JSON: {}", e)))?;
+        game.armazenamento = armazenamento;
+
+        game.sincronizar_catalogo();
         
-        Ok(())
+        Ok(game)
     }
 
+    /// Mantem o catalogo e os itens dos personagens consistentes apos carregar:
+    /// itens que ja existem no catalogo sao substituidos pela versao do catalogo,
+    /// e itens que nao existem (saves antigos) sao registrados nele.
+    fn sincronizar_catalogo(&mut self) {
+        let itens = &mut self.itens;
+
+        let mut sincronizar = |item: ItemTipo| -> ItemTipo {
+            itens.entry(item.get_id()).or_insert_with(|| {
+                let mut definicao = item;
+                if let ItemTipo::Consumivel(consumivel) = &mut definicao {
+                    consumivel.definir_quantidade(1);
+                }
+                definicao
+            }).clone()
+        };
 
-    //=============================================================================================================================
-    //================================================= FUNÇÕES ===================================================================
-    //=============================================================================================================================
-
+        for personagem in self.personagens.values_mut() {
+            for item in personagem.inventario.values_mut().chain(personagem.equipamento.itens_mut()) {
+                let mut sincronizado = sincronizar(item.clone());
 
-    //DEVO CHAMAR ESSA FUNÇÃO NA MAIN
+                // a pilha é do personagem; do catalogo vem so a definicao
+                if let (ItemTipo::Consumivel(novo), ItemTipo::Consumivel(atual)) = (&mut sincronizado, &*item) {
+                    novo.definir_quantidade(atual.get_quantidade());
+                }
 
-    // pub fn iniciar_menu(&self) {
-    //     println!("================= MENU PRINCIPAL ================= \n[1] Teste/Simulcao \n[2] Novo Jogo \n[3] Continuar Jogo (criara um novo caso não tenha um save)");
+                *item = sincronizado;
+            }
+        }
+    }
 
-    //     let entrada = ler_string();
+    /// Salva o estado atual do jogo no backend configurado.
+    /// No backend de arquivo a gravacao é atomica: um crash no meio nunca deixa o save
+    /// corrompido, e o save anterior fica guardado como backup.
+    pub fn salvar(&mut self) -> Result<(), ErroRPG> {
+        self.tempo_jogo = self.tempo_jogo();
+        self.inicio_sessao = Instant::now();
 
-    //     match entrada {
-    //         "1" => {
-                
-    //         },
-    //         "2" => {
+        let documento = self.exportar()?;
 
-    //         }
-    //         "3" => {
+        self.armazenamento.gravar(&documento)
+    }
 
-    //         }
-    //     } 
-    // }
+    /// Estado atual do jogo como documento JSON, no mesmo formato do save.
+    pub fn exportar(&self) -> Result<serde_json::Value, ErroRPG> {
+        Ok(serde_json::to_value(self)?)
+    }
 
-    pub fn listar_inventario(&self, personagem: Personagem) {
-        println!("=========== ARMADURAS ===========");
+    /// Tempo total de jogo em segundos, incluindo a sessao atual.
+    pub fn tempo_jogo(&self) -> u64 {
+        self.tempo_jogo + self.inicio_sessao.elapsed().as_secs()
+    }
 
-        let mut encontrou = false;
+    /// Muda o backend onde o jogo sera salvo no proximo `salvar`.
+    pub fn definir_armazenamento(&mut self, armazenamento: Box<dyn Armazenamento>) {
+        self.armazenamento = armazenamento;
+    }
 
-        for item in personagem.inventario.values() {
-            if let ItemTipo::Armadura(armadura) = item {
-                encontrou = true;
-                println!(
-                    "ID: {} \nNome: {}, Defesa: {}, Raridade: {}",
-                    armadura.id(),
-                    armadura.get_nome(),
-                    armadura.get_defesa(),
-                    armadura.get_raridade()
-                );
-                println!("---------------------------------------------------");
-            }
+    /// Coloca dois personagens do jogo para batalhar ate um deles morrer.
+    /// Os dois sao personagens do jogador, entao a batalha é um duelo: no fim cada um volta com
+    /// a vida que tinha antes, e o perdedor pode lutar de novo.
+    /// O vencedor recebe a experiencia da vitoria e os niveis ganhos vao em `ResultadoBatalha::niveis`.
+    pub fn batalhar(&mut self, desafiante: Uuid, oponente: Uuid) -> Result<ResultadoBatalha, ErroRPG> {
+        if desafiante == oponente {
+            return Err(ErroRPG::BatalhaInvalida(tr!("batalha.contra_si_mesmo")));
         }
 
-        if !encontrou {
-            println!("Nenhuma armadura encontrada no inventario.");
-        }
+        let [a, b] = self.personagens.get_disjoint_mut([&desafiante, &oponente]);
+        let a = a.ok_or(ErroRPG::PersonagemNaoEncontrado(desafiante))?;
+        let b = b.ok_or(ErroRPG::PersonagemNaoEncontrado(oponente))?;
+        let vidas = (a.vida, b.vida);
 
-        println!("============= ARMAS =============");
-
-        let mut encontrou = false;
-
-        for item in personagem.inventario.values() {
-            if let ItemTipo::Arma(arma) = item {
-                encontrou = true;
-                println!(
-                    "ID: {} \nNome: {}, Dano: {}, Raridade: {}, Efeito: {}",
-                    arma.id(),
-                    arma.get_nome(),
-                    arma.get_dano(),
-                    arma.get_raridade(),
-                    arma.get_efeito().as_string(),
-                );
-                println!("---------------------------------------------------");
-            }
+        let mut batalha = Batalha::new(a, b, &mut self.rng)?;
+        if self.regra_carga == RegraCarga::Penalidade {
+            batalha.penalizar_sobrecarga();
         }
 
-        if !encontrou {
-            println!("Nenhuma arma encontrada no inventario.");
-        }
+        let mut resultado = batalha.executar();
 
-        println!("=========== CONSUMIVEIS ===========");
-
-        let mut encontrou = false;
-
-        for item in personagem.inventario.values() {
-            if let ItemTipo::Consumivel(consumivel) = item {
-                encontrou = true;
-                println!(
-                    "ID: {} \nNome: {}, Cura: {}, Descricao: {}",
-                    consumivel.id(),
-                    consumivel.get_nome(),
-                    consumivel.get_efeito_vida(),
-                    consumivel.get_descricao(),
-                );
-                println!("---------------------------------------------------");
+        (a.vida, b.vida) = vidas;
+
+        if let (Some(vencedor), Some(perdedor)) = (resultado.vencedor, resultado.perdedor) {
+            resultado.niveis = self.conceder_experiencia(vencedor, resultado.experiencia)?;
+
+            let perdedor = self.personagem(perdedor)?;
+            if let Some(tabela) = self.saques.do_inimigo(&perdedor.classe).cloned() {
+                resultado.saque = self.entregar_saque(&tabela, vencedor, perdedor.nivel)?;
             }
         }
 
-        if !encontrou {
-            println!("Nenhuma consumivel encontrado no inventario.");
-        }
-        
+        Ok(resultado)
     }
 
-    pub fn listar_todas_armaduras(&self) {
-        println!("=========== ARMADURAS ===========");
+    /// Concede experiencia a um personagem (batalhas, missoes) e devolve os niveis ganhos.
+    pub fn conceder_experiencia(&mut self, id: Uuid, experiencia: u32) -> Result<Vec<EventoNivel>, ErroRPG> {
+        let personagem = self.personagens.get_mut(&id).ok_or(ErroRPG::PersonagemNaoEncontrado(id))?;
 
-        let mut encontrou = false;
+        Ok(personagem.ganhar_experiencia(experiencia, &self.curva_experiencia))
+    }
 
-        for item in self.itens.values() {
-            if let ItemTipo::Armadura(armadura) = item {
-                encontrou = true;
-                println!(
-                    "ID: {} \nNome: {}, Defesa: {}, Raridade: {}",
-                    armadura.id(),
-                    armadura.get_nome(),
-                    armadura.get_defesa(),
-                    armadura.get_raridade()
-                );
-                println!("---------------------------------------------------");
-            }
-        }
+    pub fn definir_curva_experiencia(&mut self, curva: CurvaExperiencia) {
+        self.curva_experiencia = curva;
+    }
 
-        if !encontrou {
-            println!("Nenhuma armadura encontrada.");
-        }
+    pub fn semente(&self) -> u64 {
+        self.rng.semente()
     }
 
-    pub fn listar_todas_armas(&self) {
-        println!("============= ARMAS =============");
+    /// Reinicia o gerador com uma semente conhecida, para reproduzir uma partida.
+    pub fn definir_semente(&mut self, semente: u64) {
+        self.rng = RngSemente::new(semente);
+    }
 
-        let mut encontrou = false;
 
-        for item in self.itens.values() {
-            if let ItemTipo::Arma(arma) = item {
-                encontrou = true;
-                println!(
-                    "ID: {} \nNome: {}, Dano: {}, Raridade: {}, Efeito: {}",
-                    arma.id(),
-                    arma.get_nome(),
-                    arma.get_dano(),
-                    arma.get_raridade(),
-                    arma.get_efeito().as_string(),
-                );
-                println!("---------------------------------------------------");
-            }
-        }
+    /// Personagens do jogo, sem ordem definida.
+    pub fn personagens(&self) -> impl Iterator<Item = &Personagem> {
+        self.personagens.values()
+    }
 
-        if !encontrou {
-            println!("Nenhuma arma encontrada.");
-        }
+    pub fn personagem(&self, id: Uuid) -> Result<&Personagem, ErroRPG> {
+        self.personagens.get(&id).ok_or(ErroRPG::PersonagemNaoEncontrado(id))
     }
 
-    pub fn listar_todos_consumiveis(&self) {
-        println!("=========== CONSUMIVEIS ===========");
+    pub fn personagem_mut(&mut self, id: Uuid) -> Result<&mut Personagem, ErroRPG> {
+        self.personagens.get_mut(&id).ok_or(ErroRPG::PersonagemNaoEncontrado(id))
+    }
 
-        let mut encontrou = false;
+    /// Adiciona um personagem ja montado ao jogo e devolve o ID dele.
+    pub fn adicionar_personagem(&mut self, personagem: Personagem) -> Uuid {
+        let id = personagem.id;
+        self.personagens.insert(id, personagem);
+        id
+    }
 
-        for item in self.itens.values() {
-            if let ItemTipo::Consumivel(consumivel) = item {
-                encontrou = true;
-                println!(
-                    "ID: {} \nNome: {}, Cura: {}, Descricao: {}",
-                    consumivel.id(),
-                    consumivel.get_nome(),
-                    consumivel.get_efeito_vida(),
-                    consumivel.get_descricao(),
-                );
-                println!("---------------------------------------------------");
-            }
-        }
+    /// Cria um personagem com os atributos da classe e o kit inicial dela.
+    pub fn novo_personagem(&mut self, nome: String, classe: Classe) -> Uuid {
+        let (vida, forca) = self.identificar_vida_forca(classe.clone());
+        let (armadura, arma, consumiveis, ouro) = self.equipamento_inicial(&classe);
 
-        if !encontrou {
-            println!("Nenhuma consumivel encontrado.");
+        let mut personagem = Personagem::new(nome, vida, forca, classe, armadura, arma);
+        personagem.ouro = ouro;
+
+        for consumivel in consumiveis {
+            personagem.adicionar_item(ItemTipo::Consumivel(consumivel));
         }
+
+        self.adicionar_personagem(personagem)
     }
 
-    fn criar_personagem(&mut self, simulacao: bool) {
-        println!("================= CRIAR PERSONAGEM =====================");
-        println!("Nome: ");
-        
-        let nome = ler_string();
+    /// Remove todos os personagens, para comecar um jogo novo. O catalogo de itens é mantido.
+    pub fn remover_personagens(&mut self) {
+        self.personagens.clear();
+    }
 
-        let vida;
-        let forca;
-        if let Ok(classe) = ler_classe() {
-            if simulacao {
-                vida = ler_u32_loop("Pontos de vida: ");
-                forca = ler_u32_loop("Pontos de forca: ");
+    /// Usa (ou equipa) um item do inventario do personagem.
+    pub fn usar_item(&mut self, personagem: Uuid, item: Uuid) -> Result<bool, ErroRPG> {
+        let personagem = self.personagens.get_mut(&personagem).ok_or(ErroRPG::PersonagemNaoEncontrado(personagem))?;
 
-            }
-            else {
-                (vida, forca) = self.identificar_vida_forca(classe);
-                let armadura = self.armadura_padrao();
-            }
-        
+        let item = personagem.inventario
+            .get(&item)
+            .cloned()
+            .ok_or_else(|| ErroRPG::ItemNaoEncontrado(item.to_string()))?;
 
+        item.usar(personagem)
+    }
 
-        
-            
-        }
+    /// Coloca uma copia de um item do catalogo no inventario do personagem e devolve o ID dela.
+    /// Um consumivel que o personagem ja tem aumenta a pilha em um; uma segunda copia de uma arma
+    /// ou armadura fica com um ID proprio.
+    pub fn adicionar_ao_inventario(&mut self, personagem: Uuid, item: Uuid) -> Result<Uuid, ErroRPG> {
+        let item = self.itens.get(&item).cloned().ok_or_else(|| ErroRPG::ItemNaoEncontrado(item.to_string()))?;
+        let personagem = self.personagens.get_mut(&personagem).ok_or(ErroRPG::PersonagemNaoEncontrado(personagem))?;
+
+        verificar_carga(self.regra_carga, personagem, &item)?;
+
+        Ok(guardar_item(&mut self.itens, personagem, item).get_id())
     }
 
-    fn armadura_simulacao(&mut self) -> Armadura {
-        let opcao = 3;
-        let mut armadura;
-        while opcao > 2 {   
-            if opcao == 0 {
-                let opcao = ler_u32_loop("[0] Criar uma armadura nova e equipar \n[1] Equipar uma armadura ja existente");
-                armadura = self.criar_armadura();  
-            }
-            else if opcao == 1 {
-                self.listar_todas_armaduras();
-                let id = ler_uuid_loop("ID: ");
-                let armadura: = Armadura::buscar_em(&self.itens, &id);
-                    
-            }
-        }
-    
-        armadura
-    }   
+    /// Equipa uma arma ou armadura do inventario do personagem. Sem `slot`, usa o primeiro
+    /// livre entre os que o item aceita. Devolve o slot usado.
+    pub fn equipar(&mut self, personagem: Uuid, item: Uuid, slot: Option<Slot>) -> Result<Slot, ErroRPG> {
+        let personagem = self.personagens.get_mut(&personagem).ok_or(ErroRPG::PersonagemNaoEncontrado(personagem))?;
 
-    fn criar_armadura(&mut self) -> Armadura {
-        // nome defesa raridade
-        println!("============= CRIAR ARMADURA =============");
-        
-        print!("Nome: ");
-        let nome = ler_string();
-        
-        let defesa = ler_u32_loop("Defesa: ");
-        
-        print!("Raridade: ");
-        let raridade = ler_string();
+        personagem.equipar(item, slot)
+    }
 
-        let armadura = Armadura::new(nome, defesa, raridade);
+    /// Guarda no inventario o item equipado no slot e devolve ele.
+    /// O equipamento ja conta na carga, entao guardar nunca passa da capacidade.
+    pub fn desequipar(&mut self, personagem: Uuid, slot: Slot) -> Result<ItemTipo, ErroRPG> {
+        let personagem = self.personagens.get_mut(&personagem).ok_or(ErroRPG::PersonagemNaoEncontrado(personagem))?;
 
-        self.itens.insert(armadura.id(), ItemTipo::Armadura(armadura.clone()));
+        personagem.desequipar(slot)
+    }
 
-        armadura
+    /// Tira um item (a pilha inteira, nos consumiveis) do inventario do personagem e devolve ele.
+    pub fn descartar_item(&mut self, personagem: Uuid, item: Uuid) -> Result<ItemTipo, ErroRPG> {
+        let personagem = self.personagens.get_mut(&personagem).ok_or(ErroRPG::PersonagemNaoEncontrado(personagem))?;
+
+        personagem.inventario
+            .remove(&item)
+            .ok_or_else(|| ErroRPG::ItemNaoEncontrado(item.to_string()))
     }
 
-    fn criar_arma(&mut self) -> Arma {
-        // nome defesa raridade
-        println!("============= CRIAR ARMA =============");
-        
-        print!("Nome: ");
-        let nome = ler_string();
-        
-        let dano = ler_u32_loop("Dano: ");
-        
-        let classe = ler_classe().unwrap();
+    pub fn regra_carga(&self) -> RegraCarga {
+        self.regra_carga
+    }
 
-        print!("Raridade: ");
-        let raridade = ler_string();
+    /// Troca o que acontece quando um personagem passa da capacidade de carga.
+    pub fn definir_regra_carga(&mut self, regra: RegraCarga) {
+        self.regra_carga = regra;
+    }
+
+    /// Itens do catalogo, sem ordem definida.
+    pub fn itens(&self) -> impl Iterator<Item = &ItemTipo> {
+        self.itens.values()
+    }
 
-        let efeito = ler_efeito().unwrap();
+    /// Catalogo separado por tipo.
+    pub fn listar_itens(&self) -> ListagemItens<'_> {
+        ListagemItens::new(self.itens.values())
+    }
 
+    pub fn item(&self, id: Uuid) -> Result<&ItemTipo, ErroRPG> {
+        self.itens.get(&id).ok_or_else(|| ErroRPG::ItemNaoEncontrado(id.to_string()))
+    }
 
-        let arma = Arma::new(nome, dano, classe, raridade, efeito);
+    /// Registra um item no catalogo e devolve o ID dele.
+    pub fn registrar_item(&mut self, item: ItemTipo) -> Uuid {
+        let id = item.get_id();
+        self.itens.insert(id, item);
+        id
+    }
 
-        self.itens.insert(arma.id(), ItemTipo::Arma(arma.clone()));
+    /// Item do catalogo com a mesma definicao do `novo` (nome, classe, atributos...).
+    /// Se nao houver, registra o `novo`. Assim um item com o mesmo nome mas de outra classe,
+    /// ou com atributos editados no arquivo de dados, vira uma entrada separada.
+    fn equivalente_do_catalogo(&mut self, novo: ItemTipo) -> ItemTipo {
+        if let Some(existente) = self.itens.values().find(|item| item.mesma_definicao(&novo)) {
+            return existente.clone();
+        }
 
-        arma
+        self.registrar_item(novo.clone());
+        novo
     }
 
-    fn criar_consumivel(&mut self) -> Consumivel {
-        println!("=========== CRIAR CONSUMIVEL ===========");
+    /// Equipamento inicial da classe, segundo os kits configurados.
+    /// Itens iguais (mesmo nome, classe e atributos) sao reaproveitados do catalogo.
+    fn equipamento_inicial(&mut self, classe: &Classe) -> (Armadura, Arma, Vec<Consumivel>, u32) {
+        let kit = self.kits.kit(classe).clone();
 
-        print!("Nome: ");
-        let nome = ler_string();
+        let armadura = match self.equivalente_do_catalogo(ItemTipo::Armadura(kit.armadura.criar())) {
+            ItemTipo::Armadura(armadura) => armadura,
+            _ => unreachable!(),
+        };
 
-        let efeito_vida = ler_u32_loop("Cura: ") as i32;
-    
-        print!("Descricao: ");
-        let descricao = ler_string();
+        let arma = match self.equivalente_do_catalogo(ItemTipo::Arma(kit.arma.criar(classe.clone()))) {
+            ItemTipo::Arma(arma) => arma,
+            _ => unreachable!(),
+        };
 
-        let consumivel = Consumivel::new(nome, efeito_vida, descricao);
-    
-        self.itens.insert(consumivel.id(), ItemTipo::Consumivel(consumivel.clone()));
+        let consumiveis = kit.consumiveis
+            .iter()
+            .filter_map(|definicao| {
+                let item = self.equivalente_do_catalogo(ItemTipo::Consumivel(definicao.criar()));
+
+                match item {
+                    ItemTipo::Consumivel(mut consumivel) => {
+                        consumivel.definir_quantidade(definicao.quantidade);
+                        Some(consumivel)
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        (armadura, arma, consumiveis, kit.ouro)
+    }
 
-        consumivel
+    /// Troca os kits iniciais usados na criacao de personagens.
+    pub fn definir_kits(&mut self, kits: KitsIniciais) {
+        self.kits = kits;
     }
 
+    /// Troca as tabelas de nomes usadas na geracao de itens.
+    pub fn definir_gerador(&mut self, gerador: GeradorItens) {
+        self.gerador = gerador;
+    }
 
-    fn armadura_padrao(&mut self) -> Armadura {
-        let armadura = match self.procurar_item_nome("Armadura de Couro".to_string()) {
-            Ok(ItemTipo::Armadura(a)) => a, // já é um clone correto
-            _ => {
-                // criar nova e inserir no HashMap
-                let nova = Armadura::new("Armadura de Couro".to_string(), 10, "Comum".to_string());
+    /// Gera uma arma aleatoria com o RNG do jogo e registra no catalogo.
+    /// Sem raridade, ela é sorteada pelos pesos de drop.
+    pub fn gerar_arma(&mut self, classe: &Classe, nivel: u32, raridade: Option<Raridade>) -> Arma {
+        let raridade = raridade.unwrap_or_else(|| Raridade::sortear(&mut self.rng));
+        let arma = self.gerador.gerar_arma(classe, nivel, raridade, &mut self.rng);
 
-                self.itens.insert(nova.get_id(), ItemTipo::Armadura(nova.clone()));
+        self.registrar_item(ItemTipo::Arma(arma.clone()));
+        arma
+    }
 
-                nova
-            }
-        };
+    /// Como `gerar_arma`, para armaduras.
+    pub fn gerar_armadura(&mut self, classe: &Classe, nivel: u32, raridade: Option<Raridade>) -> Armadura {
+        let raridade = raridade.unwrap_or_else(|| Raridade::sortear(&mut self.rng));
+        let armadura = self.gerador.gerar_armadura(classe, nivel, raridade, &mut self.rng);
+
+        self.registrar_item(ItemTipo::Armadura(armadura.clone()));
         armadura
     }
 
-    
+    /// Troca as tabelas de saque de inimigos e baus.
+    pub fn definir_saques(&mut self, saques: TabelasSaque) {
+        self.saques = saques;
+    }
+
+    /// Nomes dos baus que podem ser abertos.
+    pub fn baus(&self) -> Vec<&str> {
+        self.saques.baus()
+    }
+
+    /// Abre o bau para o personagem e entrega o saque. Cada personagem abre cada bau uma vez.
+    pub fn abrir_bau(&mut self, personagem: Uuid, bau: &str) -> Result<Saque, ErroRPG> {
+        let tabela = self.saques
+            .do_bau(bau)
+            .cloned()
+            .ok_or_else(|| ErroRPG::EntradaInvalida(tr!("saque.bau_desconhecido", bau, self.saques.baus().join(", "))))?;
+
+        let aberto = self.personagem_mut(personagem)?;
+        if !aberto.baus_abertos.insert(bau.to_string()) {
+            return Err(ErroRPG::EntradaInvalida(tr!("saque.bau_ja_aberto", aberto.nome, bau)));
+        }
+        let nivel = aberto.nivel;
+
+        self.entregar_saque(&tabela, personagem, nivel)
+    }
+
+    /// Sorteia a tabela e coloca o ouro e os itens com o personagem.
+    /// Itens fixos sao reaproveitados do catalogo quando ja existe um igual (armas, da mesma classe);
+    /// itens gerados sao do `nivel` informado.
+    fn entregar_saque(&mut self, tabela: &TabelaSaque, personagem: Uuid, nivel: u32) -> Result<Saque, ErroRPG> {
+        let classe = self.personagem(personagem)?.classe.clone();
+        let (ouro, sorteados) = tabela.sortear(&mut self.rng);
+
+        let mut itens = Vec::new();
+        for sorteado in sorteados {
+            let item = match sorteado {
+                ItemSaque::Nada => continue,
+                ItemSaque::Arma(definicao) => self.equivalente_do_catalogo(ItemTipo::Arma(definicao.criar(classe.clone()))),
+                ItemSaque::Armadura(definicao) => self.equivalente_do_catalogo(ItemTipo::Armadura(definicao.criar())),
+                ItemSaque::Consumivel(definicao) => {
+                    let mut item = self.equivalente_do_catalogo(ItemTipo::Consumivel(definicao.criar()));
+                    if let ItemTipo::Consumivel(consumivel) = &mut item {
+                        consumivel.definir_quantidade(definicao.quantidade);
+                    }
+                    item
+                }
+                ItemSaque::Gerado { tipo: TipoGerado::Arma, raridade } => ItemTipo::Arma(self.gerar_arma(&classe, nivel, *raridade)),
+                ItemSaque::Gerado { tipo: TipoGerado::Armadura, raridade } => ItemTipo::Armadura(self.gerar_armadura(&classe, nivel, *raridade)),
+            };
+
+            itens.push(item);
+        }
+
+        let regra = self.regra_carga;
+        let personagem = self.personagens.get_mut(&personagem).ok_or(ErroRPG::PersonagemNaoEncontrado(personagem))?;
+        personagem.ouro = personagem.ouro.saturating_add(ouro);
+
+        let mut recebidos = Vec::new();
+        let mut deixados = Vec::new();
+
+        for item in itens {
+            if verificar_carga(regra, personagem, &item).is_ok() {
+                recebidos.push(guardar_item(&mut self.itens, personagem, item));
+            } else {
+                deixados.push(item);
+            }
+        }
+
+        Ok(Saque { ouro, itens: recebidos, deixados })
+    }
 
     fn identificar_vida_forca(&self, classe: Classe) -> (u32, u32){
         let vida;
@@ -368,170 +554,286 @@ impl Game {
             }
         }
 
-        return (vida, forca);
+        (vida, forca)
     }
 
-    fn procurar_item_nome(&self, nome: String) -> Result<ItemTipo, ErroRPG> {
+    pub fn procurar_item_nome(&self, nome: String) -> Result<ItemTipo, ErroRPG> {
         self.itens
             .values()
             .find(|item| item.get_nome() == nome)
             .cloned() // ← devolve o ItemTipo inteiro
-            .ok_or_else(|| ErroRPG::ItemNaoEncontrado(nome))
+            .ok_or(ErroRPG::ItemNaoEncontrado(nome))
     }
-        
 }
 
+/// Guarda o item com o personagem e devolve ele como ficou no inventario. Uma copia de arma
+/// ou armadura que ganhou ID novo entra no catalogo com esse ID, como os outros itens dos inventarios.
+fn guardar_item(catalogo: &mut HashMap<Uuid, ItemTipo>, personagem: &mut Personagem, item: ItemTipo) -> ItemTipo {
+    let id = personagem.adicionar_item(item.clone());
 
-fn ler_classe() -> Result<Classe, ErroRPG> {
-    let opcao = 3;
-    while opcao > 2 {
-        let opcao = ler_u32_loop(
-            "Classe:
-            \n[0] Guerreiro \n[1] Mago \n[2] Assassino
-            \nOpcao: "
-        );
-        if opcao > 2 {
-            println!("O número inserido não se refere a nenhuma classe existente, tente novamente.");
-        }
+    match (&item, personagem.inventario.get(&id)) {
+        (ItemTipo::Consumivel(_), _) | (_, None) => item,
+        (_, Some(guardado)) => catalogo.entry(id).or_insert_with(|| guardado.clone()).clone(),
     }
+}
 
-    match opcao {
-        0 => return Ok(Classe::Guerreiro), 
-        1 => return Ok(Classe::Mago), 
-        2 => return Ok(Classe::Assassino), 
-        _ => return Err(ErroRPG::ClasseInvalida)
+/// Com a regra `RecusarItem`, recusa o item se ele passar o personagem da capacidade de carga.
+fn verificar_carga(regra: RegraCarga, personagem: &Personagem, item: &ItemTipo) -> Result<(), ErroRPG> {
+    if regra == RegraCarga::RecusarItem && !personagem.aguenta(item.peso_total()) {
+        return Err(ErroRPG::CapacidadeExcedida {
+            personagem: personagem.nome.clone(),
+            item: item.nome_com_quantidade(),
+            peso: personagem.peso_carregado().saturating_add(item.peso_total()),
+            capacidade: personagem.capacidade_carga(),
+        });
     }
+
+    Ok(())
 }
 
-fn ler_efeito() -> Result<Efeito, ErroRPG> {
-    let opcao = 3;
-    while opcao > 2 {
-        let opcao = ler_u32_loop(
-            "Efeito:
-            \n[0] Fisico \n[1] Congelamento \n[2] Queimadura \n[3] Veneno \n[4] Eletricidade \n[5] Sangramento \n[6] Enfraquecimento
-            \nOpcao: "
-        );
-        if opcao > 6 {
-            println!("O número inserido não se refere a nenhum efeito existente, tente novamente.");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpg_game::armazenamento::ArmazenamentoBinario;
+
+    /// Jogo com um personagem de cada classe e uma semente fixa.
+    fn jogo_exemplo(armazenamento: Box<dyn Armazenamento>) -> Game {
+        let mut game = Game::novo(armazenamento);
+        game.definir_semente(99);
+
+        for (nome, classe) in [("Ana", Classe::Guerreiro), ("Bob", Classe::Mago), ("Cid", Classe::Assassino)] {
+            game.novo_personagem(nome.to_string(), classe);
         }
+
+        game
     }
 
-    match opcao {
-        0 => return Ok(Efeito::Fisico), 
-        1 => return Ok(Efeito::Congelamento), 
-        2 => return {
-            let dano = ler_u32_loop("Dano de queimadura: ");
-            let num_rodadas = ler_u32_loop("Numero de rodadas do efeito: ");
-            Ok(Efeito::Queimadura(dano, num_rodadas))
-        }, 
-        3 => {
-            let dano = ler_f32_loop("Dano percentual por rodada: ");
-            Ok(Efeito::Veneno(dano))
-        },
-        4 => {
-            let dano = ler_u32_loop("Dano do choque: ");
-            let probabilidade = ler_u32_loop("Percentual de chance de causar choque: ");
-            Ok(Efeito::Eletricidade(dano, probabilidade))
-        },
-        5 => {
-            let dano = ler_u32_loop("Dano do sangramento: ");
-            let probabilidade = ler_u32_loop("Percentual de chance de causar choque: ");
-            Ok(Efeito::Sangramento(dano, probabilidade))
-        },
-        6 => {
-            let reducao_dano = ler_u32_loop("Percentual de reducao de dano: ")
-            Ok(Efeito::Enfraquecimento(reducao_dano))
+    #[test]
+    fn kits_com_a_mesma_arma_dao_a_arma_da_classe_certa() {
+        let kit = |dano| format!(r#"{{
+            "arma": {{ "nome": "Bastao", "dano": {dano}, "raridade": "Comum", "efeito": "Fisico" }},
+            "armadura": {{ "nome": "Tunica", "defesa": 2, "raridade": "Comum" }}
+        }}"#);
+        let kits = |dano| serde_json::from_str::<KitsIniciais>(&format!(
+            r#"{{ "Guerreiro": {0}, "Mago": {0}, "Assassino": {0} }}"#, kit(dano)
+        )).unwrap();
+
+        let mut game = Game::novo(Box::new(ArmazenamentoMemoria::new()));
+        game.definir_kits(kits(5));
+
+        let mago = game.novo_personagem("Bob".to_string(), Classe::Mago);
+        let guerreiro = game.novo_personagem("Ana".to_string(), Classe::Guerreiro);
+
+        for (id, classe) in [(mago, Classe::Mago), (guerreiro, Classe::Guerreiro)] {
+            assert!(*game.personagem(id).unwrap().get_arma().unwrap().get_classe() == classe);
         }
-        _ => return Err(ErroRPG::EfeitoInvalido)
+
+        // o kit editado vale para os personagens novos, mesmo com o nome ja no catalogo
+        game.definir_kits(kits(9));
+        let novo = game.novo_personagem("Cid".to_string(), Classe::Guerreiro);
+
+        assert_eq!(game.personagem(novo).unwrap().get_arma().unwrap().get_dano_base(), 9);
+        assert_eq!(game.personagem(guerreiro).unwrap().get_arma().unwrap().get_dano_base(), 5);
     }
-}
 
-fn ler_string() -> String {
-    let mut entrada = String::new();
+    #[test]
+    fn arma_fixa_do_saque_e_da_classe_de_quem_recebe() {
+        let saques: TabelasSaque = serde_json::from_str(r#"{
+            "tabelas": {
+                "bau": { "garantidos": [ { "Arma": { "nome": "Cajado", "dano": 5, "raridade": "Comum", "efeito": "Fisico" } } ] }
+            },
+            "baus": { "Bau": "bau" }
+        }"#).unwrap();
 
-    io::stdin()
-        .read_line(&mut entrada)
-        .expect("Erro ao ler entrada");
+        let mut game = Game::novo(Box::new(ArmazenamentoMemoria::new()));
+        game.definir_saques(saques);
 
-    entrada.trim().to_string()
-}
+        let mago = game.novo_personagem("Bob".to_string(), Classe::Mago);
+        let guerreiro = game.novo_personagem("Ana".to_string(), Classe::Guerreiro);
 
-pub fn ler_u32() -> Result<u32, ErroRPG> {
-    let mut entrada = String::new();
+        for (id, classe) in [(mago, Classe::Mago), (guerreiro, Classe::Guerreiro)] {
+            let saque = game.abrir_bau(id, "Bau").unwrap();
+            let cajado = saque.itens[0].get_id();
 
-    io::stdin()
-        .read_line(&mut entrada)
-        .map_err(|_| ErroRPG::EntradaInvalida("Erro ao ler entrada".to_string()))?;
+            assert!(matches!(&saque.itens[0], ItemTipo::Arma(arma) if *arma.get_classe() == classe));
+            game.equipar(id, cajado, None).unwrap();
+        }
+    }
 
-    let valor = entrada
-        .trim()
-        .parse::<u32>()
-        .map_err(|_| ErroRPG::EntradaInvalida("Entrada deve ser um número inteiro positivo".to_string()))?;
+    #[test]
+    fn copias_iguais_do_saque_ficam_todas_no_inventario() {
+        let saques: TabelasSaque = serde_json::from_str(r#"{
+            "tabelas": {
+                "bau": { "garantidos": [
+                    { "Armadura": { "nome": "Elmo", "defesa": 3, "raridade": "Comum", "encaixe": "Cabeca" } },
+                    { "Armadura": { "nome": "Elmo", "defesa": 3, "raridade": "Comum", "encaixe": "Cabeca" } }
+                ] }
+            },
+            "baus": { "Bau": "bau" }
+        }"#).unwrap();
+
+        let mut game = Game::novo(Box::new(ArmazenamentoMemoria::new()));
+        game.definir_saques(saques);
+        game.definir_regra_carga(RegraCarga::Penalidade);
+
+        let id = game.novo_personagem("Ana".to_string(), Classe::Guerreiro);
+        let antes = game.personagem(id).unwrap().inventario.len();
+
+        let saque = game.abrir_bau(id, "Bau").unwrap();
+        let personagem = game.personagem(id).unwrap();
+
+        assert_eq!(saque.itens.len(), 2);
+        assert_eq!(personagem.inventario.len(), antes + 2);
+        assert_ne!(saque.itens[0].get_id(), saque.itens[1].get_id());
+
+        for item in &saque.itens {
+            assert!(personagem.inventario.contains_key(&item.get_id()));
+            assert!(game.item(item.get_id()).is_ok());
+        }
 
-    Ok(valor)
-}
+        // pegar do catalogo uma copia do elmo que ja esta no inventario tambem nao sobrescreve
+        let copia = game.adicionar_ao_inventario(id, saque.itens[0].get_id()).unwrap();
 
-pub fn ler_u32_loop(prompt: &str) -> u32 {
-    loop {
-        print!("{}", prompt);
-        let _ = std::io::stdout().flush();
+        assert_ne!(copia, saque.itens[0].get_id());
+        assert_eq!(game.personagem(id).unwrap().inventario.len(), antes + 3);
+    }
 
-        match ler_u32() {
-            Ok(n) => return n,
-            Err(ErroRPG::EntradaInvalida(s)) => println!("{}", s),
-            _ => println!("Erro desconhecido ao ler entrada"),
-        }
+    #[test]
+    fn saque_com_valores_enormes_nao_estoura() {
+        let saques: TabelasSaque = serde_json::from_str(r#"{
+            "tabelas": {
+                "bau": {
+                    "ouro": [10, 10],
+                    "sorteios": 3,
+                    "entradas": [
+                        { "peso": 4294967295, "item": "Nada" },
+                        { "peso": 4294967295, "item": "Nada" }
+                    ]
+                }
+            },
+            "baus": { "Bau": "bau" }
+        }"#).unwrap();
+
+        let mut game = Game::novo(Box::new(ArmazenamentoMemoria::new()));
+        game.definir_saques(saques);
+
+        let id = game.novo_personagem("Ana".to_string(), Classe::Guerreiro);
+        game.personagem_mut(id).unwrap().ouro = u32::MAX - 1;
+
+        let saque = game.abrir_bau(id, "Bau").unwrap();
+
+        assert_eq!(saque.ouro, 10);
+        assert_eq!(game.personagem(id).unwrap().ouro, u32::MAX);
     }
-}
 
-pub fn ler_f32() -> Result<f32, String> {
-    let mut entrada = String::new();
+    #[test]
+    fn salvar_e_carregar_da_memoria() {
+        let armazenamento = ArmazenamentoMemoria::new();
+        let mut game = jogo_exemplo(Box::new(armazenamento.clone()));
 
-    io::stdout().flush().unwrap();
-    io::stdin().read_line(&mut entrada).map_err(|_| "Erro ao ler entrada".to_string())?;
+        game.salvar().unwrap();
+        let carregado = Game::carregar_de(Box::new(armazenamento.clone())).unwrap();
 
-    entrada
-        .trim()
-        .replace(",", ".") // permite usar "1,5" ou "1.5"
-        .parse::<f32>()
-        .map_err(|_| "Valor inválido".to_string())
-}
+        assert_eq!(carregado.exportar().unwrap(), game.exportar().unwrap());
+        assert_eq!(carregado.semente(), game.semente());
+        assert_eq!(armazenamento.documento().unwrap()["versao"], VERSAO_SAVE);
+    }
 
-pub fn ler_f32_loop(msg: &str) -> f32 {
-    loop {
-        print!("{}", msg);
-        std::io::stdout().flush().unwrap();
+    /// Salva pelo backend e carrega de novo por outro backend igual, no mesmo lugar.
+    fn ida_e_volta(backend: impl Fn() -> Box<dyn Armazenamento>) {
+        let mut game = jogo_exemplo(backend());
 
-        match ler_f32() {
-            Ok(num) => return num,
-            Err(_) => println!("Valor inválido! Digite novamente."),
-        }
+        game.salvar().unwrap();
+        let carregado = Game::carregar_de(backend()).unwrap();
+
+        assert_eq!(carregado.exportar().unwrap(), game.exportar().unwrap());
     }
-}
 
-pub fn ler_uuid() -> Result<Uuid, ErroRPG> {
-    let mut entrada = String::new();
+    #[test]
+    fn salvar_e_carregar_do_disco() {
+        let diretorio = tempfile::tempdir().unwrap();
+        let json = diretorio.path().join("save.json");
+        let binario = diretorio.path().join("save.cbor");
 
-    io::stdout().flush().unwrap();
-    io::stdin()
-        .read_line(&mut entrada)
-        .map_err(|_| ErroRPG::EntradaInvalida("Erro ao ler entrada".to_string()))?;
+        ida_e_volta(|| Box::new(ArmazenamentoJson::new(&json)));
+        ida_e_volta(|| Box::new(ArmazenamentoBinario::new(&binario)));
+    }
 
-    let texto = entrada.trim();
+    #[test]
+    fn backup_so_substitui_save_corrompido() {
+        let diretorio = tempfile::tempdir().unwrap();
+        let path = diretorio.path().join("save.json");
 
-    Uuid::parse_str(texto)
-        .map_err(|_| ErroRPG::EntradaInvalida(format!("UUID inválido: {}", texto)))
-}
+        let mut game = jogo_exemplo(Box::new(ArmazenamentoJson::new(&path)));
+        game.salvar().unwrap();
+        game.salvar().unwrap();
+
+        fs::write(&path, "{ corrompido").unwrap();
+        let recuperado = Game::carregar_com_backup(&path).unwrap();
+        assert_eq!(recuperado.exportar().unwrap()["personagens"], game.exportar().unwrap()["personagens"]);
+
+        let futuro = r#"{ "versao": 99, "personagens": {} }"#;
+        fs::write(&path, futuro).unwrap();
+
+        assert!(matches!(Game::carregar_com_backup(&path), Err(ErroRPG::ErroPersistencia(_))));
+        assert_eq!(fs::read_to_string(&path).unwrap(), futuro);
+    }
+
+    #[test]
+    fn restaurar_backup_volta_o_save_anterior() {
+        let diretorio = tempfile::tempdir().unwrap();
+        let path = diretorio.path().join("save.json");
 
-pub fn ler_uuid_loop(msg: &str) -> Uuid {
-    loop {
-        print!("{}", msg);
-        io::stdout().flush().unwrap();
+        let mut game = jogo_exemplo(Box::new(ArmazenamentoJson::new(&path)));
+        game.salvar().unwrap();
+        game.novo_personagem("Dan".to_string(), Classe::Mago);
+        game.salvar().unwrap();
 
-        match ler_uuid() {
-            Ok(uuid) => return uuid,
-            Err(ErroRPG::EntradaInvalida(s)) => println!("{}", s),
-            _ => println!("Erro desconhecido ao ler entrada"),
+        let restaurado = Game::restaurar_backup(&path, 1).unwrap();
+
+        assert_eq!(restaurado.personagens().count(), 3);
+        assert_eq!(fs::read(&path).unwrap(), fs::read(persistencia::caminho_backup(&path, 1)).unwrap());
+        assert!(matches!(Game::restaurar_backup(&path, 5), Err(ErroRPG::ErroPersistencia(_))));
+    }
+
+    #[test]
+    fn perdedor_pode_lutar_de_novo() {
+        let mut game = jogo_exemplo(Box::new(ArmazenamentoMemoria::new()));
+        let mut ids: Vec<_> = game.personagens().map(|p| (p.nome.clone(), p.id)).collect();
+        ids.sort();
+        let (ana, bob) = (ids[0].1, ids[1].1);
+
+        // sem subir de nivel, que tambem mexe na vida
+        game.definir_curva_experiencia(CurvaExperiencia::new(u32::MAX, 1.0));
+        game.personagem_mut(ana).unwrap().receber_dano(10);
+        let vida_bob = game.personagem(bob).unwrap().vida;
+
+        for _ in 0..2 {
+            game.batalhar(ana, bob).unwrap();
+            assert_eq!(game.personagem(ana).unwrap().vida, 90);
+            assert_eq!(game.personagem(bob).unwrap().vida, vida_bob);
         }
     }
+
+    #[test]
+    fn batalha_salva_continua_igual_depois_de_carregar() {
+        let armazenamento = ArmazenamentoMemoria::new();
+        let mut game = jogo_exemplo(Box::new(armazenamento.clone()));
+        game.salvar().unwrap();
+
+        let mut carregado = Game::carregar_de(Box::new(armazenamento)).unwrap();
+
+        let ids = |game: &Game| {
+            let mut personagens: Vec<_> = game.personagens().map(|p| (p.nome.clone(), p.id)).collect();
+            personagens.sort();
+            (personagens[0].1, personagens[1].1)
+        };
+
+        let (a, b) = ids(&game);
+        let original = game.batalhar(a, b).unwrap();
+        let (a, b) = ids(&carregado);
+        let repetida = carregado.batalhar(a, b).unwrap();
+
+        assert_eq!(serde_json::to_value(&original.log).unwrap(), serde_json::to_value(&repetida.log).unwrap());
+    }
 }
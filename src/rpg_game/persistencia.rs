@@ -0,0 +1,449 @@
+use std::{ffi::OsString, fs::{self, File}, io::Write, path::{Path, PathBuf}};
+
+use serde_json::{Map, Value};
+
+use crate::{errors::ErroRPG, rpg_game::{idioma::tr, raridade::Raridade}};
+
+/// Quantidade de backups mantidos ao lado do save por padrao.
+pub const BACKUPS_PADRAO: usize = 3;
+
+/// Versao atual do formato do save. Deve ser incrementada junto com uma nova migracao
+/// sempre que `Game`, `Personagem` ou os itens mudarem de forma incompativel.
+pub const VERSAO_SAVE: u32 = 4;
+
+type Migracao = fn(&mut Value) -> Result<(), ErroRPG>;
+
+/// `MIGRACOES[n]` leva um save da versao `n` para a versao `n + 1`.
+const MIGRACOES: [Migracao; VERSAO_SAVE as usize] = [
+    migrar_v0_para_v1,
+    migrar_v1_para_v2,
+    migrar_v2_para_v3,
+    migrar_v3_para_v4,
+];
+
+/// Atualiza um documento de save, passo a passo, ate a versao atual.
+/// Saves sem o campo `versao` sao tratados como versao 0.
+/// Documentos que nao sao um objeto JSON (`[]`, `42`, ...) sao recusados com erro de persistencia.
+pub fn migrar(documento: &mut Value) -> Result<(), ErroRPG> {
+    if !documento.is_object() {
+        return Err(ErroRPG::ErroPersistencia(tr!("save.formato_invalido")));
+    }
+
+    let versao = verificar_versao(documento)?;
+
+    for migracao in &MIGRACOES[versao as usize..] {
+        migracao(documento)?;
+    }
+
+    documento["versao"] = Value::from(VERSAO_SAVE);
+
+    Ok(())
+}
+
+/// Versao do save (0 se ele nao tiver o campo `versao`). Um save de uma versao mais nova
+/// que a deste jogo é recusado: ele nao esta corrompido, so nao pode ser lido por este jogo.
+pub fn verificar_versao(documento: &Value) -> Result<u32, ErroRPG> {
+    let versao = documento
+        .get("versao")
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+        .min(u32::MAX as u64) as u32;
+
+    if versao > VERSAO_SAVE {
+        return Err(ErroRPG::ErroPersistencia(tr!("save.versao_futura", versao, VERSAO_SAVE)));
+    }
+
+    Ok(versao)
+}
+
+fn personagens_mut(documento: &mut Value) -> impl Iterator<Item = &mut serde_json::Map<String, Value>> {
+    documento
+        .get_mut("personagens")
+        .and_then(Value::as_object_mut)
+        .into_iter()
+        .flat_map(|personagens| personagens.values_mut())
+        .filter_map(Value::as_object_mut)
+}
+
+/// v0 -> v1: personagens ganham `vida_maxima` (igual a vida atual) e `experiencia`.
+fn migrar_v0_para_v1(documento: &mut Value) -> Result<(), ErroRPG> {
+    for personagem in personagens_mut(documento) {
+        let vida = personagem.get("vida").cloned().unwrap_or(Value::from(0));
+
+        personagem.entry("vida_maxima").or_insert(vida);
+        personagem.entry("experiencia").or_insert(Value::from(0));
+    }
+
+    Ok(())
+}
+
+/// v1 -> v2: a raridade de armas e armaduras deixa de ser texto livre e vira `Raridade`;
+/// textos que nao correspondem a nenhuma raridade viram `Comum`. A defesa do personagem
+/// inclui a da armadura equipada, entao ganha a diferenca do novo multiplicador.
+fn migrar_v1_para_v2(documento: &mut Value) -> Result<(), ErroRPG> {
+    if let Some(itens) = documento.get_mut("itens").and_then(Value::as_object_mut) {
+        for item in itens.values_mut() {
+            converter_raridade_item(item)?;
+        }
+    }
+
+    for personagem in personagens_mut(documento) {
+        if let Some(inventario) = personagem.get_mut("inventario").and_then(Value::as_object_mut) {
+            for item in inventario.values_mut() {
+                converter_raridade_item(item)?;
+            }
+        }
+
+        if let Some(arma) = personagem.get_mut("arma") {
+            converter_raridade(arma)?;
+        }
+
+        let Some(armadura) = personagem.get_mut("armadura") else {
+            continue;
+        };
+
+        let raridade = converter_raridade(armadura)?;
+        let defesa_base = armadura.get("defesa").and_then(Value::as_u64).unwrap_or(0) as u32;
+        let bonus = raridade.aplicar(defesa_base).saturating_sub(defesa_base);
+
+        if let Some(defesa) = personagem.get("defesa").and_then(Value::as_u64) {
+            personagem.insert("defesa".to_string(), Value::from(defesa + bonus as u64));
+        }
+    }
+
+    Ok(())
+}
+
+/// v2 -> v3: consumiveis passam a empilhar. Consumiveis iguais (mesmo nome, efeito e descricao)
+/// no inventario de um personagem viram uma pilha so, com a soma das quantidades. A pilha fica
+/// com o ID que existe no catalogo, se algum existir, para nao duplicar a definicao la.
+fn migrar_v2_para_v3(documento: &mut Value) -> Result<(), ErroRPG> {
+    let catalogo: Vec<String> = documento
+        .get("itens")
+        .and_then(Value::as_object)
+        .map(|itens| itens.keys().cloned().collect())
+        .unwrap_or_default();
+
+    for personagem in personagens_mut(documento) {
+        let Some(inventario) = personagem.get_mut("inventario").and_then(Value::as_object_mut) else {
+            continue;
+        };
+
+        // (nome, efeito, descricao) -> ID da pilha que fica
+        let mut pilhas: Vec<((Value, Value, Value), String)> = Vec::new();
+        let mut repetidos: Vec<(String, String)> = Vec::new();
+
+        for (id, item) in inventario.iter_mut() {
+            let Some(consumivel) = item.get_mut("Consumivel").and_then(Value::as_object_mut) else {
+                continue;
+            };
+
+            consumivel.entry("quantidade").or_insert(Value::from(1));
+
+            let definicao = (
+                consumivel.get("nome").cloned().unwrap_or_default(),
+                consumivel.get("efeito_vida").cloned().unwrap_or_default(),
+                consumivel.get("descricao").cloned().unwrap_or_default(),
+            );
+
+            match pilhas.iter_mut().find(|(existente, _)| *existente == definicao) {
+                Some((_, pilha)) if catalogo.contains(id) && !catalogo.contains(pilha) => {
+                    repetidos.push((std::mem::replace(pilha, id.clone()), id.clone()));
+                }
+                Some((_, pilha)) => repetidos.push((id.clone(), pilha.clone())),
+                None => pilhas.push((definicao, id.clone())),
+            }
+        }
+
+        for (repetido, pilha) in repetidos {
+            let quantidade = inventario
+                .remove(&repetido)
+                .and_then(|item| item["Consumivel"]["quantidade"].as_u64())
+                .unwrap_or(1);
+
+            let total = &mut inventario[&pilha]["Consumivel"]["quantidade"];
+            *total = Value::from(total.as_u64().unwrap_or(1) + quantidade);
+        }
+    }
+
+    Ok(())
+}
+
+/// v3 -> v4: o equipamento passa a ser por slot. A arma vai para a mao principal e a armadura
+/// para o peito. A defesa do personagem deixa de incluir a da armadura, que agora é somada
+/// a partir dos slots.
+fn migrar_v3_para_v4(documento: &mut Value) -> Result<(), ErroRPG> {
+    for personagem in personagens_mut(documento) {
+        let mut equipamento = Map::new();
+
+        if let Some(arma) = personagem.remove("arma") {
+            equipar_migrado(&mut equipamento, "MaoPrincipal", "Arma", arma);
+        }
+
+        if let Some(armadura) = personagem.remove("armadura") {
+            let raridade: Raridade = armadura
+                .get("raridade")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()?
+                .unwrap_or_default();
+            let defesa_armadura = raridade.aplicar(armadura.get("defesa").and_then(Value::as_u64).unwrap_or(0) as u32);
+
+            if let Some(defesa) = personagem.get("defesa").and_then(Value::as_u64) {
+                personagem.insert("defesa".to_string(), Value::from(defesa.saturating_sub(defesa_armadura as u64)));
+            }
+
+            equipar_migrado(&mut equipamento, "Peito", "Armadura", armadura);
+        }
+
+        personagem.entry("equipamento").or_insert(Value::Object(equipamento));
+    }
+
+    Ok(())
+}
+
+/// Saves antigos usavam um item chamado "Nenhuma" para slot vazio; ele nao vira equipamento.
+fn equipar_migrado(equipamento: &mut Map<String, Value>, slot: &str, tipo: &str, item: Value) {
+    if item.get("nome").and_then(Value::as_str) == Some("Nenhuma") {
+        return;
+    }
+
+    let mut item_tipo = Map::new();
+    item_tipo.insert(tipo.to_string(), item);
+
+    equipamento.insert(slot.to_string(), Value::Object(item_tipo));
+}
+
+/// Itens no formato do `ItemTipo`: `{"Arma": {...}}`, `{"Armadura": {...}}` ou `{"Consumivel": {...}}`.
+fn converter_raridade_item(item: &mut Value) -> Result<(), ErroRPG> {
+    for tipo in ["Arma", "Armadura"] {
+        if let Some(interno) = item.get_mut(tipo) {
+            converter_raridade(interno)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn converter_raridade(item: &mut Value) -> Result<Raridade, ErroRPG> {
+    let raridade = item
+        .get("raridade")
+        .and_then(Value::as_str)
+        .and_then(Raridade::de_texto)
+        .unwrap_or_default();
+
+    if let Some(item) = item.as_object_mut() {
+        item.insert("raridade".to_string(), serde_json::to_value(raridade)?);
+    }
+
+    Ok(raridade)
+}
+
+/// Caminho do `n`-esimo backup de um save (`save.json.bak1`, `save.json.bak2`, ...).
+/// O backup 1 é sempre o mais recente.
+pub fn caminho_backup(path: &Path, n: usize) -> PathBuf {
+    caminho_com_sufixo(path, &format!(".bak{}", n))
+}
+
+fn caminho_com_sufixo(path: &Path, sufixo: &str) -> PathBuf {
+    let mut nome = path.file_name().map(OsString::from).unwrap_or_default();
+    nome.push(sufixo);
+    path.with_file_name(nome)
+}
+
+/// Grava o conteudo sem nunca deixar o save pela metade: escreve num arquivo temporario,
+/// garante que ele chegou no disco e so entao o renomeia por cima do save.
+/// O save anterior é guardado como backup 1 e os backups antigos sao rotacionados,
+/// mantendo no maximo `backups` copias.
+pub fn gravar_atomico(path: &Path, conteudo: &[u8], backups: usize) -> Result<(), ErroRPG> {
+    let temporario = caminho_com_sufixo(path, ".tmp");
+
+    let mut file = File::create(&temporario)?;
+
+    file.write_all(conteudo)
+        .and_then(|_| file.sync_all())?;
+
+    if backups > 0 && path.exists() {
+        rotacionar_backups(path, backups)?;
+    }
+
+    fs::rename(&temporario, path)?;
+
+    Ok(())
+}
+
+fn rotacionar_backups(path: &Path, backups: usize) -> Result<(), ErroRPG> {
+    for n in (1..backups).rev() {
+        let origem = caminho_backup(path, n);
+
+        if origem.exists() {
+            fs::rename(&origem, caminho_backup(path, n + 1))?;
+        }
+    }
+
+    // copia em vez de mover, para o save principal continuar no lugar ate o rename final
+    fs::copy(path, caminho_backup(path, 1))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use crate::{rpg_game::{Game, armazenamento::{Armazenamento, ArmazenamentoMemoria}, equipamento::Slot, itens::ItemTipo}, traits::ItemComportamento};
+
+    const ANA: &str = "00000000-0000-0000-0000-0000000000a1";
+    const BOB: &str = "00000000-0000-0000-0000-0000000000b1";
+    const CID: &str = "00000000-0000-0000-0000-0000000000c1";
+
+    /// Save do formato original: caminho dentro do save, raridade em texto livre, sem catalogo,
+    /// pocoes repetidas em entradas separadas e a armadura "Nenhuma" no lugar de slot vazio.
+    const SAVE_V0: &str = r#"{
+        "persistencia_path": "save.json",
+        "personagens": {
+            "00000000-0000-0000-0000-0000000000a1": {
+                "id": "00000000-0000-0000-0000-0000000000a1",
+                "nome": "Ana", "vida": 80, "forca": 15, "nivel": 1, "defesa": 10, "classe": "Guerreiro",
+                "armadura": { "id": "00000000-0000-0000-0000-0000000000a2", "nome": "Cota", "defesa": 10, "raridade": "Rara" },
+                "arma": { "id": "00000000-0000-0000-0000-0000000000a3", "nome": "Espada", "dano": 8, "classe": "Guerreiro", "efeito": "Fisico", "raridade": "épica" },
+                "inventario": {
+                    "00000000-0000-0000-0000-0000000000a4": { "Consumivel": { "id": "00000000-0000-0000-0000-0000000000a4", "nome": "Pocao", "efeito_vida": 20, "descricao": "Cura" } },
+                    "00000000-0000-0000-0000-0000000000a5": { "Consumivel": { "id": "00000000-0000-0000-0000-0000000000a5", "nome": "Pocao", "efeito_vida": 20, "descricao": "Cura" } }
+                }
+            },
+            "00000000-0000-0000-0000-0000000000b1": {
+                "id": "00000000-0000-0000-0000-0000000000b1",
+                "nome": "Bob", "vida": 70, "forca": 20, "nivel": 1, "defesa": 0, "classe": "Mago",
+                "armadura": { "id": "00000000-0000-0000-0000-0000000000b2", "nome": "Nenhuma", "defesa": 0, "raridade": "nenhuma" },
+                "arma": { "id": "00000000-0000-0000-0000-0000000000b3", "nome": "Cajado", "dano": 6, "classe": "Mago", "efeito": "Fisico", "raridade": "de estimacao" },
+                "inventario": {}
+            }
+        }
+    }"#;
+
+    /// Save da versao 3: raridades ja convertidas, pilhas de consumiveis e a defesa da armadura
+    /// ainda somada na defesa do personagem.
+    const SAVE_V3: &str = r#"{
+        "versao": 3,
+        "itens": {},
+        "personagens": {
+            "00000000-0000-0000-0000-0000000000c1": {
+                "id": "00000000-0000-0000-0000-0000000000c1",
+                "nome": "Cid", "vida": 60, "vida_maxima": 60, "forca": 28, "nivel": 2, "experiencia": 5,
+                "defesa": 9, "classe": "Assassino",
+                "armadura": { "id": "00000000-0000-0000-0000-0000000000c2", "nome": "Capuz", "defesa": 6, "raridade": "Incomum" },
+                "arma": { "id": "00000000-0000-0000-0000-0000000000c3", "nome": "Adaga", "dano": 6, "classe": "Assassino", "efeito": "Fisico", "raridade": "Raro" },
+                "inventario": {
+                    "00000000-0000-0000-0000-0000000000c4": { "Consumivel": { "id": "00000000-0000-0000-0000-0000000000c4", "nome": "Pocao", "efeito_vida": 20, "descricao": "Cura", "quantidade": 3 } }
+                }
+            }
+        }
+    }"#;
+
+    fn carregar(save: &str) -> Game {
+        let mut armazenamento = ArmazenamentoMemoria::new();
+        armazenamento.gravar(&serde_json::from_str(save).unwrap()).unwrap();
+
+        Game::carregar_de(Box::new(armazenamento)).unwrap()
+    }
+
+    fn id(texto: &str) -> Uuid {
+        Uuid::parse_str(texto).unwrap()
+    }
+
+    fn nome_no_slot(game: &Game, personagem: &str, slot: Slot) -> Option<String> {
+        game.personagem(id(personagem)).unwrap().equipamento.get(slot).map(ItemTipo::get_nome)
+    }
+
+    #[test]
+    fn gravacao_rotaciona_os_backups() {
+        let diretorio = tempfile::tempdir().unwrap();
+        let path = diretorio.path().join("save.json");
+
+        for conteudo in ["1", "2", "3", "4"] {
+            gravar_atomico(&path, conteudo.as_bytes(), 2).unwrap();
+        }
+
+        let ler = |path: &Path| fs::read_to_string(path).unwrap();
+        assert_eq!(ler(&path), "4");
+        assert_eq!(ler(&caminho_backup(&path, 1)), "3");
+        assert_eq!(ler(&caminho_backup(&path, 2)), "2");
+        assert!(!caminho_backup(&path, 3).exists());
+        assert!(!caminho_com_sufixo(&path, ".tmp").exists());
+    }
+
+    #[test]
+    fn save_v0_chega_na_versao_atual() {
+        let game = carregar(SAVE_V0);
+        assert_eq!(game.exportar().unwrap()["versao"], VERSAO_SAVE);
+
+        // a Cota rara vale 15; na v2 a defesa ganha o bonus de 5 e na v4 perde a armadura inteira
+        let ana = game.personagem(id(ANA)).unwrap();
+        assert_eq!(ana.vida_maxima, 80);
+        assert_eq!(ana.defesa, 0);
+        assert_eq!(ana.defesa_total(), 15);
+        assert_eq!(ana.get_arma().unwrap().get_dano(), 14);
+        assert_eq!(nome_no_slot(&game, ANA, Slot::Peito).as_deref(), Some("Cota"));
+        assert_eq!(nome_no_slot(&game, ANA, Slot::MaoPrincipal).as_deref(), Some("Espada"));
+
+        let pocoes = ana.listar_inventario().consumiveis;
+        assert_eq!(pocoes.len(), 1);
+        assert_eq!(pocoes[0].get_quantidade(), 2);
+
+        // a "Nenhuma" nao vira equipamento e a raridade desconhecida vira Comum
+        let bob = game.personagem(id(BOB)).unwrap();
+        assert_eq!(nome_no_slot(&game, BOB, Slot::Peito), None);
+        assert_eq!(bob.defesa_total(), 0);
+        assert_eq!(bob.get_arma().unwrap().get_dano(), 6);
+    }
+
+    #[test]
+    fn save_v3_separa_a_defesa_da_armadura() {
+        let game = carregar(SAVE_V3);
+        let cid = game.personagem(id(CID)).unwrap();
+
+        // Capuz incomum: 6 * 1.2 = 7, que sai da defesa propria de 9
+        assert_eq!(cid.defesa, 2);
+        assert_eq!(cid.defesa_total(), 9);
+        assert_eq!(nome_no_slot(&game, CID, Slot::Peito).as_deref(), Some("Capuz"));
+        assert_eq!(nome_no_slot(&game, CID, Slot::MaoPrincipal).as_deref(), Some("Adaga"));
+        assert_eq!(cid.listar_inventario().consumiveis[0].get_quantidade(), 3);
+        assert!(cid.inventario.values().all(|item| !matches!(item, ItemTipo::Arma(_) | ItemTipo::Armadura(_))));
+    }
+
+    #[test]
+    fn pilha_migrada_fica_com_o_id_do_catalogo() {
+        let pocao = |id: &str, quantidade: u32| json!({ "Consumivel": {
+            "id": id, "nome": "Pocao", "efeito_vida": 20, "descricao": "Cura", "quantidade": quantidade
+        } });
+        let (fora, catalogo) = ("00000000-0000-0000-0000-0000000000d1", "00000000-0000-0000-0000-0000000000d2");
+
+        let mut documento = json!({
+            "versao": 2,
+            "itens": { catalogo: pocao(catalogo, 1) },
+            "personagens": { "p": { "inventario": { fora: pocao(fora, 2), catalogo: pocao(catalogo, 3) } } }
+        });
+
+        migrar_v2_para_v3(&mut documento).unwrap();
+
+        let inventario = documento["personagens"]["p"]["inventario"].as_object().unwrap();
+        assert_eq!(inventario.len(), 1);
+        assert_eq!(inventario[catalogo]["Consumivel"]["quantidade"], 5);
+    }
+
+    #[test]
+    fn save_que_nao_e_objeto_e_recusado() {
+        for mut documento in [json!([]), json!(42), json!("x"), Value::Null] {
+            assert!(matches!(migrar(&mut documento), Err(ErroRPG::ErroPersistencia(_))));
+        }
+
+        let diretorio = tempfile::tempdir().unwrap();
+        let path = diretorio.path().join("save.json");
+        fs::write(&path, "[]").unwrap();
+
+        assert!(matches!(Game::carregar(&path), Err(ErroRPG::ErroPersistencia(_))));
+    }
+}
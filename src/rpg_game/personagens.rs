@@ -1,24 +1,36 @@
-use std::{collections::HashMap, ptr::null};
+use std::collections::{HashMap, HashSet};
 
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
-use crate::rpg_game::itens::{Arma, Armadura, ItemTipo};
+use crate::{errors::ErroRPG, rpg_game::{carga::{CARGA_BASE, CARGA_POR_FORCA}, equipamento::{Equipamento, Slot}, idioma::tr, itens::{Arma, Armadura, ItemTipo, ListagemItens}, progressao::{CurvaExperiencia, EventoNivel}}, traits::ItemComportamento};
 
 #[derive(Serialize, Deserialize)]
 pub struct Personagem {
     pub id: Uuid,
     pub nome: String,
     pub vida: u32,
+    #[serde(default)]
+    pub vida_maxima: u32,
     pub forca:u32,
     pub nivel:u32,
-    pub armadura: Armadura,
+    /// Experiencia acumulada dentro do nivel atual.
+    #[serde(default)]
+    pub experiencia: u32,
+    /// Defesa propria do personagem, ganha nos niveis. A do equipamento é somada em `defesa_total`.
     pub defesa: u32,
-    pub arma: Arma,
+    /// Itens equipados, um por slot. Nao contam no inventario, mas contam na carga.
+    #[serde(default)]
+    pub equipamento: Equipamento,
     pub classe: Classe,
     pub inventario: HashMap<Uuid, ItemTipo>,
+    #[serde(default)]
+    pub ouro: u32,
+    /// Baus que o personagem ja abriu; cada bau so entrega o saque uma vez por personagem.
+    #[serde(default)]
+    pub baus_abertos: HashSet<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Classe {
     Guerreiro,
     Mago,
@@ -33,6 +45,25 @@ impl Classe {
             Classe::Assassino => "Assassino",
         }
     }
+
+    /// Nome da classe no idioma atual, para mostrar ao jogador.
+    /// `as_str` continua sendo o identificador usado nos saves e arquivos de dados.
+    pub fn nome(&self) -> String {
+        match self {
+            Classe::Guerreiro => tr!("classe.guerreiro"),
+            Classe::Mago => tr!("classe.mago"),
+            Classe::Assassino => tr!("classe.assassino"),
+        }
+    }
+
+    /// Atributos ganhos a cada nivel: (vida, forca, defesa).
+    pub fn crescimento_por_nivel(&self) -> (u32, u32, u32) {
+        match self {
+            Classe::Guerreiro => (12, 2, 2),
+            Classe::Mago => (7, 3, 1),
+            Classe::Assassino => (6, 4, 1),
+        }
+    }
 }
 
 impl Personagem {
@@ -55,25 +86,347 @@ impl Personagem {
             }
         }
 
+        let mut equipamento = Equipamento::default();
+        equipamento.colocar(armadura.get_encaixe().slots()[0], ItemTipo::Armadura(armadura));
+        equipamento.colocar(arma.get_encaixe().slots()[0], ItemTipo::Arma(arma));
+
         Personagem {
             id: Uuid::new_v4(),
             nome,
             vida,
+            vida_maxima: vida,
             forca,
             nivel: 1,
-            armadura: armadura.clone(),
-            defesa: armadura.get_defesa(),
-            arma,
+            experiencia: 0,
+            defesa: 0,
+            equipamento,
             classe,
-            inventario: HashMap::new()
+            inventario: HashMap::new(),
+            ouro: 0,
+            baus_abertos: HashSet::new(),
+        }
+    }
+
+    /// Arma da mao principal, se houver.
+    pub fn get_arma(&self) -> Option<&Arma> {
+        self.equipamento.arma(Slot::MaoPrincipal)
+    }
+
+    /// Armadura do peito, se houver.
+    pub fn get_armadura(&self) -> Option<&Armadura> {
+        match self.equipamento.get(Slot::Peito) {
+            Some(ItemTipo::Armadura(armadura)) => Some(armadura),
+            _ => None,
+        }
+    }
+
+    /// Forca mais o dano das armas equipadas. As contas de atributos saturam em `u32::MAX`,
+    /// ja que a simulacao aceita qualquer valor digitado.
+    pub fn ataque(&self) -> u32 {
+        self.forca.saturating_add(self.equipamento.dano())
+    }
+
+    /// Defesa do personagem mais a de todas as armaduras equipadas.
+    pub fn defesa_total(&self) -> u32 {
+        self.defesa.saturating_add(self.equipamento.defesa())
+    }
+
+    /// Tira o item do inventario e o equipa em `slot` ou, sem slot, no primeiro livre
+    /// entre os que o item aceita. O que estava no slot volta para o inventario.
+    /// Devolve o slot usado.
+    pub fn equipar(&mut self, item: Uuid, slot: Option<Slot>) -> Result<Slot, ErroRPG> {
+        let equipavel = self.inventario.get(&item).ok_or_else(|| ErroRPG::ItemNaoEncontrado(item.to_string()))?;
+
+        let encaixe = equipavel
+            .encaixe()
+            .ok_or_else(|| ErroRPG::ItemNaoUsavel(tr!("equipamento.nao_equipavel", equipavel.get_nome())))?;
+
+        if let ItemTipo::Arma(arma) = equipavel && *arma.get_classe() != self.classe {
+            return Err(ErroRPG::ArmaIncompativel(tr!("item.arma_outra_classe")));
+        }
+
+        if self.equipamento.slot_do_item(item).is_some() {
+            return Err(ErroRPG::ItemNaoUsavel(tr!("item.ja_equipado")));
+        }
+
+        let slot = match slot {
+            Some(slot) if !slot.aceita(encaixe) => {
+                return Err(ErroRPG::SlotIncompativel { item: equipavel.get_nome(), slot: slot.nome() });
+            }
+            Some(slot) => slot,
+            None => self.equipamento.slot_para(encaixe),
+        };
+
+        if let Some(item) = self.inventario.remove(&item)
+            && let Some(anterior) = self.equipamento.colocar(slot, item)
+        {
+            self.inventario.insert(anterior.get_id(), anterior);
+        }
+
+        Ok(slot)
+    }
+
+    /// Tira o item do slot e o guarda no inventario. Devolve uma copia do item guardado.
+    pub fn desequipar(&mut self, slot: Slot) -> Result<ItemTipo, ErroRPG> {
+        let item = self.equipamento.tirar(slot).ok_or_else(|| ErroRPG::SlotVazio(slot.nome()))?;
+
+        self.inventario.insert(item.get_id(), item.clone());
+
+        Ok(item)
+    }
+
+    /// Coloca o item no inventario e devolve o ID com que ele ficou guardado. Consumiveis entram
+    /// na pilha do mesmo item (mesmo ID ou mesma definicao), se houver; senao abrem uma pilha nova.
+    /// Armas e armaduras nao empilham: uma copia de um item que o personagem ja tem, no inventario
+    /// ou equipado, ganha um ID novo para nao sobrescrever a outra.
+    pub fn adicionar_item(&mut self, item: ItemTipo) -> Uuid {
+        if let ItemTipo::Consumivel(novo) = &item {
+            let pilha = self.inventario.values_mut().find_map(|existente| match existente {
+                ItemTipo::Consumivel(pilha) if pilha.get_id() == novo.get_id() || pilha.mesma_definicao(novo) => Some(pilha),
+                _ => None,
+            });
+
+            if let Some(pilha) = pilha {
+                pilha.definir_quantidade(pilha.get_quantidade().saturating_add(novo.get_quantidade()));
+                return pilha.get_id();
+            }
+        }
+
+        let item = if self.tem_item(item.get_id()) { item.com_novo_id() } else { item };
+        let id = item.get_id();
+
+        self.inventario.insert(id, item);
+        id
+    }
+
+    /// Se o item com esse ID esta no inventario ou equipado.
+    pub fn tem_item(&self, id: Uuid) -> bool {
+        self.inventario.contains_key(&id) || self.equipamento.slot_do_item(id).is_some()
+    }
+
+    /// Peso que o personagem aguenta carregar, contando o equipamento.
+    pub fn capacidade_carga(&self) -> u32 {
+        self.forca.saturating_mul(CARGA_POR_FORCA).saturating_add(CARGA_BASE)
+    }
+
+    /// Peso do inventario mais o de todo o equipamento.
+    pub fn peso_carregado(&self) -> u32 {
+        self.inventario
+            .values()
+            .map(ItemTipo::peso_total)
+            .fold(self.equipamento.peso(), u32::saturating_add)
+    }
+
+    /// Quanto o peso carregado passa da capacidade, em % da capacidade (0 se nao passar).
+    pub fn sobrecarga(&self) -> u32 {
+        let capacidade = self.capacidade_carga().max(1) as u64;
+        let excesso = (self.peso_carregado() as u64).saturating_sub(capacidade);
+
+        (excesso * 100 / capacidade).min(u32::MAX as u64) as u32
+    }
+
+    /// Se o personagem aguenta carregar mais `peso` sem passar da capacidade.
+    pub fn aguenta(&self, peso: u32) -> bool {
+        self.peso_carregado() as u64 + peso as u64 <= self.capacidade_carga() as u64
+    }
+
+    /// Itens do inventario separados por tipo.
+    pub fn listar_inventario(&self) -> ListagemItens<'_> {
+        ListagemItens::new(self.inventario.values())
+    }
+
+    pub fn esta_vivo(&self) -> bool {
+        self.vida > 0
+    }
+
+    /// Reduz a vida sem passar de zero. Devolve o dano efetivamente sofrido.
+    pub fn receber_dano(&mut self, dano: u32) -> u32 {
+        let sofrido = dano.min(self.vida);
+        self.vida -= sofrido;
+        sofrido
+    }
+
+    /// Recupera vida sem passar de `vida_maxima`. Devolve quanto foi curado.
+    pub fn curar(&mut self, cura: u32) -> u32 {
+        let curado = cura.min(self.vida_maxima.saturating_sub(self.vida));
+        self.vida += curado;
+        curado
+    }
+
+    /// Adiciona experiencia e sobe quantos niveis forem possiveis pela curva, sem passar do nivel
+    /// `u32::MAX`. Devolve um evento para cada nivel ganho.
+    pub fn ganhar_experiencia(&mut self, experiencia: u32, curva: &CurvaExperiencia) -> Vec<EventoNivel> {
+        let mut eventos = Vec::new();
+
+        self.experiencia = self.experiencia.saturating_add(experiencia);
+
+        while self.nivel < u32::MAX && self.experiencia >= curva.experiencia_para_subir(self.nivel) {
+            self.experiencia -= curva.experiencia_para_subir(self.nivel);
+            self.nivel += 1;
+
+            let (vida, forca, defesa) = self.classe.crescimento_por_nivel();
+            self.vida_maxima = self.vida_maxima.saturating_add(vida);
+            self.vida = self.vida.saturating_add(vida);
+            self.forca = self.forca.saturating_add(forca);
+            self.defesa = self.defesa.saturating_add(defesa);
+
+            eventos.push(EventoNivel {
+                personagem: self.nome.clone(),
+                nivel: self.nivel,
+                vida_ganha: vida,
+                forca_ganha: forca,
+                defesa_ganha: defesa,
+            });
         }
+
+        eventos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpg_game::{equipamento::Encaixe, itens::{Consumivel, Efeito}, progressao::experiencia_da_vitoria, raridade::Raridade};
+
+    fn personagem() -> Personagem {
+        Personagem::new(
+            "Ana".to_string(), 0, 0, Classe::Guerreiro,
+            Armadura::new("Tunica".to_string(), 2, Raridade::Comum),
+            Arma::new("Espada".to_string(), 5, Classe::Guerreiro, Raridade::Comum, Efeito::Fisico),
+        )
+    }
+
+    fn pocao(peso: u32, quantidade: u32) -> ItemTipo {
+        let mut pocao = Consumivel::new("Pocao".to_string(), 20, "Cura".to_string());
+        pocao.definir_peso(peso);
+        pocao.definir_quantidade(quantidade);
+        ItemTipo::Consumivel(pocao)
+    }
+
+    #[test]
+    fn pilha_enorme_nao_estoura() {
+        let mut personagem = personagem();
+
+        let pilha = personagem.adicionar_item(pocao(1, u32::MAX));
+        personagem.adicionar_item(pocao(1, 3));
+
+        assert_eq!(personagem.inventario.len(), 1);
+        assert!(matches!(&personagem.inventario[&pilha], ItemTipo::Consumivel(c) if c.get_quantidade() == u32::MAX));
     }
 
-    pub fn get_arma(&self) -> &Arma {
-        &self.arma
+    fn armadura(nome: &str, encaixe: Encaixe) -> ItemTipo {
+        let mut armadura = Armadura::new(nome.to_string(), 1, Raridade::Comum);
+        armadura.definir_encaixe(encaixe);
+        ItemTipo::Armadura(armadura)
     }
 
-    pub fn get_armadura(&self) -> &Armadura {
-        &self.armadura
+    #[test]
+    fn equipar_e_desequipar_trocam_itens_com_o_inventario() {
+        let mut personagem = personagem();
+        let espada = personagem.get_arma().unwrap().get_id();
+
+        let machado = personagem.adicionar_item(ItemTipo::Arma(
+            Arma::new("Machado".to_string(), 9, Classe::Guerreiro, Raridade::Comum, Efeito::Fisico),
+        ));
+        assert_eq!(personagem.equipar(machado, None).unwrap(), Slot::MaoPrincipal);
+        assert!(personagem.inventario.contains_key(&espada));
+        assert!(!personagem.inventario.contains_key(&machado));
+        assert_eq!(personagem.ataque(), personagem.forca + 9);
+
+        let aneis = [
+            personagem.adicionar_item(armadura("Anel", Encaixe::Anel)),
+            personagem.adicionar_item(armadura("Anel", Encaixe::Anel)),
+        ];
+        assert_eq!(personagem.equipar(aneis[0], None).unwrap(), Slot::Anel1);
+        assert_eq!(personagem.equipar(aneis[1], None).unwrap(), Slot::Anel2);
+
+        let elmo = personagem.adicionar_item(armadura("Elmo", Encaixe::Cabeca));
+        assert!(matches!(personagem.equipar(elmo, Some(Slot::Peito)), Err(ErroRPG::SlotIncompativel { .. })));
+        assert!(personagem.inventario.contains_key(&elmo));
+
+        let cajado = personagem.adicionar_item(ItemTipo::Arma(
+            Arma::new("Cajado".to_string(), 4, Classe::Mago, Raridade::Comum, Efeito::Fisico),
+        ));
+        assert!(matches!(personagem.equipar(cajado, None), Err(ErroRPG::ArmaIncompativel(_))));
+
+        assert_eq!(personagem.desequipar(Slot::Anel1).unwrap().get_id(), aneis[0]);
+        assert!(personagem.inventario.contains_key(&aneis[0]));
+        assert!(personagem.equipamento.get(Slot::Anel1).is_none());
+        assert!(matches!(personagem.desequipar(Slot::Anel1), Err(ErroRPG::SlotVazio(_))));
+    }
+
+    #[test]
+    fn usar_consome_a_pilha_ate_acabar() {
+        let mut personagem = personagem();
+        personagem.receber_dano(60);
+
+        let pilha = personagem.adicionar_item(pocao(1, 2));
+        let usar = |personagem: &mut Personagem| personagem.inventario[&pilha].clone().usar(personagem);
+
+        usar(&mut personagem).unwrap();
+        assert_eq!(personagem.vida, 60);
+        assert!(matches!(&personagem.inventario[&pilha], ItemTipo::Consumivel(c) if c.get_quantidade() == 1));
+
+        usar(&mut personagem).unwrap();
+        assert_eq!(personagem.vida, 80);
+        assert!(!personagem.inventario.contains_key(&pilha));
+    }
+
+    #[test]
+    fn consumivel_de_outro_peso_abre_outra_pilha() {
+        let mut personagem = personagem();
+
+        let leve = personagem.adicionar_item(pocao(1, 2));
+        let pesada = personagem.adicionar_item(pocao(10, 1));
+
+        assert_ne!(leve, pesada);
+        assert_eq!(personagem.listar_inventario().consumiveis.len(), 2);
+        assert_eq!(personagem.peso_carregado() - personagem.equipamento.peso(), 12);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn dano_e_cura_ficam_entre_zero_e_a_vida_maxima() {
+        let mut personagem = personagem();
+
+        assert_eq!(personagem.receber_dano(30), 30);
+        assert_eq!(personagem.vida, 70);
+        assert_eq!(personagem.curar(50), 30);
+        assert_eq!(personagem.vida, 100);
+        assert_eq!(personagem.curar(10), 0);
+
+        assert_eq!(personagem.receber_dano(u32::MAX), 100);
+        assert_eq!(personagem.vida, 0);
+        assert!(!personagem.esta_vivo());
+        assert_eq!(personagem.receber_dano(5), 0);
+    }
+
+    #[test]
+    fn nivel_para_no_maximo() {
+        let mut personagem = personagem();
+        personagem.nivel = u32::MAX - 1;
+
+        let eventos = personagem.ganhar_experiencia(10, &CurvaExperiencia::new(1, 0.0));
+
+        assert_eq!(eventos.len(), 1);
+        assert_eq!(personagem.nivel, u32::MAX);
+        assert_eq!(personagem.experiencia, 9);
+        assert_eq!(experiencia_da_vitoria(&personagem), u32::MAX);
+    }
+
+    #[test]
+    fn atributos_enormes_nao_estouram() {
+        let mut personagem = Personagem::new(
+            "Titan".to_string(), 10, u32::MAX, Classe::Guerreiro,
+            Armadura::new("Tunica".to_string(), u32::MAX, Raridade::Comum),
+            Arma::new("Espada".to_string(), u32::MAX, Classe::Guerreiro, Raridade::Comum, Efeito::Fisico),
+        );
+        personagem.defesa = u32::MAX;
+
+        assert_eq!(personagem.capacidade_carga(), u32::MAX);
+        assert_eq!(personagem.ataque(), u32::MAX);
+        assert_eq!(personagem.defesa_total(), u32::MAX);
+        assert!(personagem.aguenta(u32::MAX - personagem.peso_carregado()));
+        assert!(!personagem.aguenta(u32::MAX));
+        assert_eq!(personagem.sobrecarga(), 0);
+    }
+}
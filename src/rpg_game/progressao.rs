@@ -0,0 +1,93 @@
+use serde::{Serialize, Deserialize};
+
+use crate::rpg_game::{idioma::tr, personagens::Personagem};
+
+/// Experiencia base concedida por nivel do personagem derrotado.
+pub const EXPERIENCIA_POR_NIVEL_DERROTADO: u32 = 50;
+
+/// Curva de experiencia: para sair do nivel `n` sao necessarios `base * n^expoente` pontos.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CurvaExperiencia {
+    pub base: u32,
+    pub expoente: f32,
+}
+
+impl Default for CurvaExperiencia {
+    fn default() -> Self {
+        CurvaExperiencia {
+            base: 100,
+            expoente: 1.5,
+        }
+    }
+}
+
+impl CurvaExperiencia {
+    pub fn new(base: u32, expoente: f32) -> Self {
+        CurvaExperiencia { base, expoente }
+    }
+
+    /// Experiencia necessaria para passar do `nivel` atual para o seguinte.
+    pub fn experiencia_para_subir(&self, nivel: u32) -> u32 {
+        ((self.base as f32 * (nivel as f32).powf(self.expoente)).round() as u32).max(1)
+    }
+}
+
+/// Subida de nivel, com os atributos ganhos.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EventoNivel {
+    pub personagem: String,
+    pub nivel: u32,
+    pub vida_ganha: u32,
+    pub forca_ganha: u32,
+    pub defesa_ganha: u32,
+}
+
+impl EventoNivel {
+    pub fn descrever(&self) -> String {
+        tr!(
+            "evento.nivel",
+            self.personagem, self.nivel, self.vida_ganha, self.forca_ganha, self.defesa_ganha
+        )
+    }
+}
+
+/// Experiencia ganha por derrotar o personagem, limitada a `u32::MAX`.
+pub fn experiencia_da_vitoria(derrotado: &Personagem) -> u32 {
+    EXPERIENCIA_POR_NIVEL_DERROTADO.saturating_mul(derrotado.nivel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpg_game::{itens::{Arma, Armadura, Efeito}, personagens::Classe, raridade::Raridade};
+
+    #[test]
+    fn curva_padrao_cresce_com_o_nivel() {
+        let curva = CurvaExperiencia::default();
+
+        assert_eq!(curva.experiencia_para_subir(1), 100);
+        assert_eq!(curva.experiencia_para_subir(2), 283);
+        assert_eq!(curva.experiencia_para_subir(4), 800);
+        assert_eq!(CurvaExperiencia::new(0, 1.0).experiencia_para_subir(5), 1);
+    }
+
+    #[test]
+    fn experiencia_sobe_varios_niveis_de_uma_vez() {
+        let mut personagem = Personagem::new(
+            "Ana".to_string(), 0, 0, Classe::Guerreiro,
+            Armadura::new("Tunica".to_string(), 2, Raridade::Comum),
+            Arma::new("Espada".to_string(), 5, Classe::Guerreiro, Raridade::Comum, Efeito::Fisico),
+        );
+        personagem.vida = 50;
+
+        // 100 para sair do nivel 1 e 283 para sair do 2; sobram 17
+        let eventos = personagem.ganhar_experiencia(400, &CurvaExperiencia::default());
+
+        assert_eq!(eventos.iter().map(|evento| evento.nivel).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(personagem.nivel, 3);
+        assert_eq!(personagem.experiencia, 17);
+        assert_eq!((personagem.vida_maxima, personagem.vida), (124, 74));
+        assert_eq!((personagem.forca, personagem.defesa), (19, 4));
+        assert_eq!(experiencia_da_vitoria(&personagem), 150);
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Serialize, Deserialize};
+
+use crate::rpg_game::{aleatorio::FonteAleatoria, idioma::tr};
+
+/// Raridade de armas e armaduras. Define quanto o atributo base do item é ampliado
+/// e com que frequencia o item aparece em saques.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Raridade {
+    #[default]
+    Comum,
+    Incomum,
+    Raro,
+    Epico,
+    Lendario,
+}
+
+impl Raridade {
+    /// Da mais comum para a mais rara.
+    pub const TODAS: [Raridade; 5] = [
+        Raridade::Comum,
+        Raridade::Incomum,
+        Raridade::Raro,
+        Raridade::Epico,
+        Raridade::Lendario,
+    ];
+
+    /// Multiplicador aplicado ao dano da arma ou a defesa da armadura, em milesimos
+    /// (1200 = 1,2x), para a conta ser exata com qualquer `u32`.
+    pub fn multiplicador(&self) -> u32 {
+        match self {
+            Raridade::Comum => 1000,
+            Raridade::Incomum => 1200,
+            Raridade::Raro => 1500,
+            Raridade::Epico => 1800,
+            Raridade::Lendario => 2500,
+        }
+    }
+
+    /// Peso relativo da raridade num sorteio de saque.
+    pub fn peso_drop(&self) -> u32 {
+        match self {
+            Raridade::Comum => 60,
+            Raridade::Incomum => 25,
+            Raridade::Raro => 10,
+            Raridade::Epico => 4,
+            Raridade::Lendario => 1,
+        }
+    }
+
+    /// Cor ANSI usada para destacar o nome da raridade no terminal.
+    pub fn cor(&self) -> &'static str {
+        match self {
+            Raridade::Comum => "\x1b[37m",
+            Raridade::Incomum => "\x1b[32m",
+            Raridade::Raro => "\x1b[34m",
+            Raridade::Epico => "\x1b[35m",
+            Raridade::Lendario => "\x1b[33m",
+        }
+    }
+
+    /// Nome da raridade no idioma atual.
+    pub fn nome(&self) -> String {
+        match self {
+            Raridade::Comum => tr!("raridade.comum"),
+            Raridade::Incomum => tr!("raridade.incomum"),
+            Raridade::Raro => tr!("raridade.raro"),
+            Raridade::Epico => tr!("raridade.epico"),
+            Raridade::Lendario => tr!("raridade.lendario"),
+        }
+    }
+
+    /// Nome da raridade pintado com a cor dela.
+    pub fn nome_colorido(&self) -> String {
+        format!("{}{}\x1b[0m", self.cor(), self.nome())
+    }
+
+    /// Aplica o multiplicador a um atributo base, arredondando para o inteiro mais proximo.
+    /// O resultado fica limitado a `u32::MAX`.
+    pub fn aplicar(&self, valor: u32) -> u32 {
+        let multiplicado = (valor as u64 * self.multiplicador() as u64 + 500) / 1000;
+
+        multiplicado.min(u32::MAX as u64) as u32
+    }
+
+    /// Interpreta a raridade escrita a mao (saves antigos, arquivos de dados), em portugues
+    /// ou ingles, com ou sem acento e sem diferenciar maiusculas.
+    pub fn de_texto(texto: &str) -> Option<Raridade> {
+        let normalizado: String = texto
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| match c {
+                'á' | 'à' | 'â' | 'ã' => 'a',
+                'é' | 'ê' => 'e',
+                'í' => 'i',
+                'ó' | 'ô' | 'õ' => 'o',
+                'ú' => 'u',
+                outro => outro,
+            })
+            .collect();
+
+        match normalizado.as_str() {
+            "comum" | "common" => Some(Raridade::Comum),
+            "incomum" | "uncommon" => Some(Raridade::Incomum),
+            "raro" | "rara" | "rare" => Some(Raridade::Raro),
+            "epico" | "epica" | "epic" => Some(Raridade::Epico),
+            "lendario" | "lendaria" | "legendary" => Some(Raridade::Lendario),
+            _ => None,
+        }
+    }
+
+    /// Sorteia uma raridade de acordo com os pesos de drop.
+    pub fn sortear(rng: &mut dyn FonteAleatoria) -> Raridade {
+        let total: u32 = Self::TODAS.iter().map(Raridade::peso_drop).sum();
+        let mut sorteado = rng.intervalo(1, total);
+
+        for raridade in Self::TODAS {
+            if sorteado <= raridade.peso_drop() {
+                return raridade;
+            }
+            sorteado -= raridade.peso_drop();
+        }
+
+        Raridade::Comum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplicador_e_exato_para_valores_grandes() {
+        assert_eq!(Raridade::Comum.aplicar(16_777_217), 16_777_217);
+        assert_eq!(Raridade::Comum.aplicar(u32::MAX), u32::MAX);
+        assert_eq!(Raridade::Incomum.aplicar(6), 7);
+        assert_eq!(Raridade::Raro.aplicar(5), 8);
+        assert_eq!(Raridade::Epico.aplicar(8), 14);
+        assert_eq!(Raridade::Lendario.aplicar(u32::MAX), u32::MAX);
+    }
+}
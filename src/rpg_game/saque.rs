@@ -0,0 +1,165 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{errors::ErroRPG, rpg_game::{aleatorio::FonteAleatoria, gerador::TipoGerado, idioma::tr, itens::ItemTipo, kits::{DefinicaoArma, DefinicaoArmadura, DefinicaoConsumivel}, personagens::Classe, raridade::Raridade}};
+
+/// Tabelas usadas quando nenhum arquivo de dados é informado.
+const SAQUES_PADRAO: &str = include_str!("../../dados/saques.json");
+
+/// O que uma entrada de saque entrega.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ItemSaque {
+    /// Entrada vazia, para a chance de nao cair nada.
+    Nada,
+    /// Arma fixa, da classe de quem recebe.
+    Arma(DefinicaoArma),
+    Armadura(DefinicaoArmadura),
+    Consumivel(DefinicaoConsumivel),
+    /// Item do gerador, no nivel do saque. Sem raridade, ela é sorteada pelos pesos de drop.
+    Gerado {
+        tipo: TipoGerado,
+        #[serde(default)]
+        raridade: Option<Raridade>,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EntradaSaque {
+    pub peso: u32,
+    pub item: ItemSaque,
+}
+
+/// Uma tabela de saque: ouro numa faixa, itens que sempre caem e `sorteios`
+/// sorteios independentes entre as entradas, de acordo com os pesos.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TabelaSaque {
+    /// Faixa fechada `[min, max]` de ouro.
+    #[serde(default)]
+    pub ouro: (u32, u32),
+    #[serde(default)]
+    pub garantidos: Vec<ItemSaque>,
+    #[serde(default = "um")]
+    pub sorteios: u32,
+    #[serde(default)]
+    pub entradas: Vec<EntradaSaque>,
+}
+
+fn um() -> u32 {
+    1
+}
+
+impl TabelaSaque {
+    /// Sorteia o ouro e os itens da tabela. Os itens ainda precisam ser criados por quem recebe.
+    pub fn sortear(&self, rng: &mut dyn FonteAleatoria) -> (u32, Vec<&ItemSaque>) {
+        let ouro = rng.intervalo(self.ouro.0, self.ouro.1);
+        let mut itens: Vec<&ItemSaque> = self.garantidos.iter().collect();
+
+        // pesos vem do arquivo de dados; somados alem de u32::MAX, as ultimas entradas so ficam mais raras
+        let total = self.entradas.iter().map(|entrada| entrada.peso).fold(0, u32::saturating_add);
+
+        if total > 0 {
+            for _ in 0..self.sorteios {
+                let mut sorteado = rng.intervalo(1, total);
+
+                for entrada in &self.entradas {
+                    if sorteado <= entrada.peso {
+                        itens.push(&entrada.item);
+                        break;
+                    }
+                    sorteado -= entrada.peso;
+                }
+            }
+        }
+
+        itens.retain(|item| !matches!(item, ItemSaque::Nada));
+
+        (ouro, itens)
+    }
+}
+
+/// Todas as tabelas de saque e a quem elas pertencem, no formato de `dados/saques.json`.
+/// Inimigos sao ligados a uma tabela pela classe; baus, pelo nome.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TabelasSaque {
+    tabelas: HashMap<String, TabelaSaque>,
+    #[serde(default)]
+    inimigos: HashMap<Classe, String>,
+    #[serde(default)]
+    baus: HashMap<String, String>,
+}
+
+impl TabelasSaque {
+    pub fn padrao() -> Self {
+        serde_json::from_str(SAQUES_PADRAO).expect("dados/saques.json embutido deve ser valido")
+    }
+
+    pub fn carregar(path: impl AsRef<Path>) -> Result<Self, ErroRPG> {
+        let conteudo = fs::read_to_string(path)?;
+
+        let saques: TabelasSaque = serde_json::from_str(&conteudo)?;
+
+        for tabela in saques.inimigos.values().chain(saques.baus.values()) {
+            if !saques.tabelas.contains_key(tabela) {
+                return Err(ErroRPG::ErroPersistencia(tr!("saque.tabela_inexistente", tabela)));
+            }
+        }
+
+        Ok(saques)
+    }
+
+    /// Tabela de quem derrota um personagem da classe, se houver.
+    pub fn do_inimigo(&self, classe: &Classe) -> Option<&TabelaSaque> {
+        self.inimigos.get(classe).and_then(|tabela| self.tabelas.get(tabela))
+    }
+
+    pub fn do_bau(&self, bau: &str) -> Option<&TabelaSaque> {
+        self.baus.get(bau).and_then(|tabela| self.tabelas.get(tabela))
+    }
+
+    /// Nomes dos baus, em ordem alfabetica.
+    pub fn baus(&self) -> Vec<&str> {
+        let mut baus: Vec<&str> = self.baus.keys().map(String::as_str).collect();
+        baus.sort();
+        baus
+    }
+}
+
+/// O que um personagem recebeu de um saque; os itens ja estao no inventario dele.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Saque {
+    pub ouro: u32,
+    pub itens: Vec<ItemTipo>,
+    /// Itens que caíram mas ficaram para tras por passar da capacidade de carga.
+    #[serde(default)]
+    pub deixados: Vec<ItemTipo>,
+}
+
+impl Saque {
+    pub fn vazio(&self) -> bool {
+        self.ouro == 0 && self.itens.is_empty() && self.deixados.is_empty()
+    }
+
+    /// Uma linha por recompensa, para mostrar ao jogador.
+    pub fn descrever(&self, personagem: &str) -> Vec<String> {
+        if self.vazio() {
+            return vec![tr!("saque.nada", personagem)];
+        }
+
+        let mut linhas = Vec::new();
+
+        if self.ouro > 0 {
+            linhas.push(tr!("saque.ouro", personagem, self.ouro));
+        }
+
+        for item in &self.itens {
+            linhas.push(tr!("saque.item", personagem, item.nome_com_quantidade()));
+        }
+
+        for item in &self.deixados {
+            linhas.push(tr!("saque.deixado", personagem, item.nome_com_quantidade()));
+        }
+
+        linhas
+    }
+}
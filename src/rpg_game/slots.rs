@@ -0,0 +1,262 @@
+use std::{fs, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{errors::ErroRPG, rpg_game::{armazenamento::ArmazenamentoJson, idioma::tr, persistencia, Game}};
+
+const EXTENSAO_SAVE: &str = "json";
+const EXTENSAO_METADADOS: &str = "meta.json";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ResumoPersonagem {
+    pub nome: String,
+    pub classe: String,
+    pub nivel: u32,
+}
+
+/// Informacoes de um slot, guardadas num arquivo separado para que a listagem
+/// nao precise carregar o jogo inteiro.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MetadadosSlot {
+    pub nome: String,
+    pub personagens: Vec<ResumoPersonagem>,
+    /// Tempo total de jogo, em segundos.
+    pub tempo_jogo: u64,
+    /// Momento do ultimo save, em segundos desde 1970-01-01 (UTC).
+    pub ultimo_salvamento: u64,
+}
+
+/// Guarda varios jogos num diretorio, um por slot.
+/// Cada slot `nome` ocupa `nome.json` (o save e seus backups) e `nome.meta.json`.
+pub struct GerenciadorSlots {
+    diretorio: PathBuf,
+}
+
+impl GerenciadorSlots {
+    pub fn new(diretorio: impl AsRef<Path>) -> Result<Self, ErroRPG> {
+        let diretorio = diretorio.as_ref().to_path_buf();
+
+        fs::create_dir_all(&diretorio)?;
+
+        Ok(GerenciadorSlots { diretorio })
+    }
+
+    /// Lista os slots existentes lendo apenas os metadados, do save mais recente ao mais antigo.
+    /// Metadados que nao podem ser lidos sao ignorados, para um arquivo estragado nao esconder os outros slots.
+    pub fn listar(&self) -> Result<Vec<MetadadosSlot>, ErroRPG> {
+        let entradas = fs::read_dir(&self.diretorio)?;
+
+        let mut slots = Vec::new();
+
+        for entrada in entradas.flatten() {
+            let nome_arquivo = entrada.file_name().to_string_lossy().to_string();
+
+            if nome_arquivo.ends_with(&format!(".{}", EXTENSAO_METADADOS))
+                && let Ok(metadados) = ler_metadados(&entrada.path())
+            {
+                slots.push(metadados);
+            }
+        }
+
+        slots.sort_by_key(|slot| std::cmp::Reverse(slot.ultimo_salvamento));
+
+        Ok(slots)
+    }
+
+    pub fn existe(&self, nome: &str) -> bool {
+        self.caminho_save(nome).exists()
+    }
+
+    /// Carrega o jogo do slot, recorrendo aos backups se o save estiver corrompido.
+    pub fn carregar(&self, nome: &str) -> Result<Game, ErroRPG> {
+        validar_nome(nome)?;
+
+        if !self.existe(nome) {
+            return Err(ErroRPG::ErroPersistencia(tr!("slot.inexistente", nome)));
+        }
+
+        Game::carregar_com_backup(self.caminho_save(nome))
+    }
+
+    /// Salva o jogo no slot (criando-o se preciso) e atualiza os metadados.
+    pub fn salvar(&self, nome: &str, game: &mut Game) -> Result<(), ErroRPG> {
+        validar_nome(nome)?;
+
+        game.definir_armazenamento(Box::new(ArmazenamentoJson::new(self.caminho_save(nome))));
+        game.salvar()?;
+
+        let metadados = MetadadosSlot {
+            nome: nome.to_string(),
+            personagens: game
+                .personagens
+                .values()
+                .map(|p| ResumoPersonagem {
+                    nome: p.nome.clone(),
+                    classe: p.classe.as_str().to_string(),
+                    nivel: p.nivel,
+                })
+                .collect(),
+            tempo_jogo: game.tempo_jogo(),
+            ultimo_salvamento: agora(),
+        };
+
+        let conteudo = serde_json::to_vec_pretty(&metadados)?;
+
+        persistencia::gravar_atomico(&self.caminho_metadados(nome), &conteudo, 0)
+    }
+
+    pub fn copiar(&self, origem: &str, destino: &str) -> Result<(), ErroRPG> {
+        self.validar_transferencia(origem, destino)?;
+
+        for (de, para) in self.arquivos_do_slot(origem, destino) {
+            fs::copy(&de, &para)?;
+        }
+
+        self.corrigir_nome_metadados(destino)
+    }
+
+    pub fn renomear(&self, origem: &str, destino: &str) -> Result<(), ErroRPG> {
+        self.validar_transferencia(origem, destino)?;
+
+        for (de, para) in self.arquivos_do_slot(origem, destino) {
+            fs::rename(&de, &para)?;
+        }
+
+        self.corrigir_nome_metadados(destino)
+    }
+
+    /// Apaga o save, os backups e os metadados do slot.
+    pub fn excluir(&self, nome: &str) -> Result<(), ErroRPG> {
+        validar_nome(nome)?;
+
+        if !self.existe(nome) {
+            return Err(ErroRPG::ErroPersistencia(tr!("slot.inexistente", nome)));
+        }
+
+        for (arquivo, _) in self.arquivos_do_slot(nome, nome) {
+            fs::remove_file(&arquivo)?;
+        }
+
+        Ok(())
+    }
+
+    fn caminho_save(&self, nome: &str) -> PathBuf {
+        self.diretorio.join(format!("{}.{}", nome, EXTENSAO_SAVE))
+    }
+
+    fn caminho_metadados(&self, nome: &str) -> PathBuf {
+        self.diretorio.join(format!("{}.{}", nome, EXTENSAO_METADADOS))
+    }
+
+    /// Pares (arquivo do slot `origem`, arquivo equivalente no slot `destino`) que existem em disco.
+    fn arquivos_do_slot(&self, origem: &str, destino: &str) -> Vec<(PathBuf, PathBuf)> {
+        let mut arquivos = vec![
+            (self.caminho_save(origem), self.caminho_save(destino)),
+            (self.caminho_metadados(origem), self.caminho_metadados(destino)),
+        ];
+
+        let backups = (1..)
+            .map(|n| {
+                (
+                    persistencia::caminho_backup(&self.caminho_save(origem), n),
+                    persistencia::caminho_backup(&self.caminho_save(destino), n),
+                )
+            })
+            .take_while(|(backup, _)| backup.exists());
+
+        arquivos.extend(backups);
+        arquivos.retain(|(arquivo, _)| arquivo.exists());
+
+        arquivos
+    }
+
+    fn validar_transferencia(&self, origem: &str, destino: &str) -> Result<(), ErroRPG> {
+        validar_nome(origem)?;
+        validar_nome(destino)?;
+
+        if !self.existe(origem) {
+            return Err(ErroRPG::ErroPersistencia(tr!("slot.inexistente", origem)));
+        }
+
+        if self.existe(destino) {
+            return Err(ErroRPG::ErroPersistencia(tr!("slot.ja_existe", destino)));
+        }
+
+        Ok(())
+    }
+
+    fn corrigir_nome_metadados(&self, nome: &str) -> Result<(), ErroRPG> {
+        let caminho = self.caminho_metadados(nome);
+
+        if !caminho.exists() {
+            return Ok(());
+        }
+
+        let mut metadados = ler_metadados(&caminho)?;
+        metadados.nome = nome.to_string();
+
+        let conteudo = serde_json::to_vec_pretty(&metadados)?;
+
+        persistencia::gravar_atomico(&caminho, &conteudo, 0)
+    }
+}
+
+fn ler_metadados(caminho: &Path) -> Result<MetadadosSlot, ErroRPG> {
+    let conteudo = fs::read(caminho)?;
+
+    Ok(serde_json::from_slice(&conteudo)?)
+}
+
+/// O nome vira nome de arquivo, entao nao pode ser vazio nem conter separadores de caminho.
+/// Pontos tambem sao recusados: o save do slot `a.meta` seria o arquivo de metadados do slot `a`.
+fn validar_nome(nome: &str) -> Result<(), ErroRPG> {
+    let invalido = nome.is_empty()
+        || nome.contains(['/', '\\', '.']);
+
+    if invalido {
+        return Err(ErroRPG::EntradaInvalida(tr!("slot.nome_invalido", nome)));
+    }
+
+    Ok(())
+}
+
+fn agora() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpg_game::armazenamento::ArmazenamentoMemoria;
+
+    #[test]
+    fn nomes_com_ponto_sao_recusados() {
+        let diretorio = tempfile::tempdir().unwrap();
+        let slots = GerenciadorSlots::new(diretorio.path()).unwrap();
+        let mut game = Game::novo(Box::new(ArmazenamentoMemoria::new()));
+
+        slots.salvar("foo", &mut game).unwrap();
+
+        assert!(matches!(slots.salvar("foo.meta", &mut game), Err(ErroRPG::EntradaInvalida(_))));
+        assert!(matches!(slots.salvar("..", &mut game), Err(ErroRPG::EntradaInvalida(_))));
+        assert_eq!(slots.listar().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn listar_ignora_metadados_ilegiveis() {
+        let diretorio = tempfile::tempdir().unwrap();
+        let slots = GerenciadorSlots::new(diretorio.path()).unwrap();
+        let mut game = Game::novo(Box::new(ArmazenamentoMemoria::new()));
+
+        slots.salvar("foo", &mut game).unwrap();
+        fs::write(diretorio.path().join("estragado.meta.json"), "{}").unwrap();
+
+        let listados = slots.listar().unwrap();
+
+        assert_eq!(listados.len(), 1);
+        assert_eq!(listados[0].nome, "foo");
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Serialize, Deserialize};
+
+use crate::rpg_game::{aleatorio::FonteAleatoria, batalha::EventoCombate, carga::{chance_lentidao, penalidade_dano}, idioma::tr, itens::Efeito, personagens::Personagem};
+
+/// Pontos de congelamento necessarios para o personagem perder a vez.
+pub const PONTOS_CONGELAMENTO: u32 = 3;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Queimadura {
+    pub dano: u32,
+    pub rodadas_restantes: u32,
+}
+
+/// Efeitos ativos sobre um combatente durante uma batalha.
+/// Os efeitos sao aplicados pelos golpes do adversario e descartados quando a batalha termina.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct StatusAtivo {
+    pub congelamento: u32,
+    pub congelado: bool,
+    pub queimadura: Option<Queimadura>,
+    pub veneno: Option<f32>,
+    pub eletricidade: Option<(u32, u32)>,
+    pub enfraquecimento: Option<u32>,
+    /// Quanto o combatente esta acima da capacidade de carga, em %, quando a regra de carga penaliza.
+    #[serde(default)]
+    pub sobrecarga: u32,
+}
+
+impl StatusAtivo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aplica o efeito da arma que acertou o personagem.
+    /// Efeitos repetidos acumulam (congelamento) ou ficam com o valor mais forte.
+    pub fn aplicar(&mut self, efeito: &Efeito, alvo: &Personagem, eventos: &mut Vec<EventoCombate>) {
+        match efeito {
+            // queimadura de 0 rodadas nao tem o que fazer
+            Efeito::Fisico | Efeito::Sangramento(_, _) | Efeito::Queimadura(_, 0) => return,
+
+            Efeito::Congelamento => {
+                self.congelamento += 1;
+                if self.congelamento >= PONTOS_CONGELAMENTO {
+                    self.congelamento = 0;
+                    self.congelado = true;
+                }
+            }
+
+            Efeito::Queimadura(dano, rodadas) => {
+                let atual = self.queimadura.get_or_insert(Queimadura { dano: 0, rodadas_restantes: 0 });
+                atual.dano = atual.dano.max(*dano);
+                atual.rodadas_restantes = atual.rodadas_restantes.max(*rodadas);
+            }
+
+            Efeito::Veneno(percentual) => {
+                self.veneno = Some(self.veneno.unwrap_or(0.0).max(*percentual));
+            }
+
+            Efeito::Eletricidade(dano, probabilidade) => {
+                let (dano_atual, probabilidade_atual) = self.eletricidade.unwrap_or((0, 0));
+                self.eletricidade = Some((dano_atual.max(*dano), probabilidade_atual.max(*probabilidade)));
+            }
+
+            Efeito::Enfraquecimento(reducao) => {
+                self.enfraquecimento = Some(self.enfraquecimento.unwrap_or(0).max(*reducao));
+            }
+        }
+
+        eventos.push(EventoCombate::EfeitoAplicado {
+            personagem: alvo.nome.clone(),
+            efeito: self.descricao_efeito(efeito),
+        });
+    }
+
+    /// Aplica o dano de queimadura e veneno no inicio da rodada e expira as queimaduras terminadas.
+    pub fn tick(&mut self, personagem: &mut Personagem, eventos: &mut Vec<EventoCombate>) {
+        if let Some(queimadura) = &mut self.queimadura {
+            personagem.receber_dano(queimadura.dano);
+            queimadura.rodadas_restantes = queimadura.rodadas_restantes.saturating_sub(1);
+
+            eventos.push(EventoCombate::DanoEfeito {
+                personagem: personagem.nome.clone(),
+                efeito: tr!("efeito.queimadura"),
+                dano: queimadura.dano,
+                vida_restante: personagem.vida,
+            });
+
+            if queimadura.rodadas_restantes == 0 {
+                self.queimadura = None;
+                eventos.push(EventoCombate::EfeitoExpirado {
+                    personagem: personagem.nome.clone(),
+                    efeito: tr!("efeito.queimadura"),
+                });
+            }
+        }
+
+        if let Some(percentual) = self.veneno {
+            if !personagem.esta_vivo() {
+                return;
+            }
+
+            let dano = personagem.receber_dano(((personagem.vida as f32 * percentual / 100.0) as u32).max(1));
+
+            eventos.push(EventoCombate::DanoEfeito {
+                personagem: personagem.nome.clone(),
+                efeito: tr!("efeito.veneno"),
+                dano,
+                vida_restante: personagem.vida,
+            });
+        }
+    }
+
+    /// Consome o congelamento: devolve `true` se o personagem perde esta vez.
+    pub fn perde_a_vez(&mut self) -> bool {
+        std::mem::take(&mut self.congelado)
+    }
+
+    /// Sorteia o choque da eletricidade ao tentar atacar. Devolve o dano do choque se ocorrer.
+    pub fn sofre_choque(&self, rng: &mut dyn FonteAleatoria) -> Option<u32> {
+        match self.eletricidade {
+            Some((dano, probabilidade)) if rng.chance(probabilidade) => Some(dano),
+            _ => None,
+        }
+    }
+
+    /// Sorteia se o peso extra faz o personagem perder a vez. Sem sobrecarga, nada é sorteado.
+    pub fn perde_a_vez_por_peso(&self, rng: &mut dyn FonteAleatoria) -> bool {
+        self.sobrecarga > 0 && rng.chance(chance_lentidao(self.sobrecarga))
+    }
+
+    /// Reduz o dano causado pelo personagem enfraquecido ou sobrecarregado.
+    pub fn reduzir_dano(&self, dano: u32) -> u32 {
+        let dano = match self.enfraquecimento {
+            Some(reducao) => aplicar_percentual(dano, 100 - reducao.min(100)).max(1),
+            None => dano,
+        };
+
+        if self.sobrecarga > 0 {
+            aplicar_percentual(dano, 100 - penalidade_dano(self.sobrecarga)).max(1)
+        } else {
+            dano
+        }
+    }
+
+    fn descricao_efeito(&self, efeito: &Efeito) -> String {
+        match efeito {
+            Efeito::Congelamento if self.congelado => tr!("efeito.congelado"),
+            Efeito::Congelamento => tr!("efeito.congelamento_pontos", self.congelamento, PONTOS_CONGELAMENTO),
+            _ => efeito.as_string(),
+        }
+    }
+}
+
+/// Dano extra do sangramento, sorteado a cada golpe da arma com esse efeito.
+pub fn dano_critico(efeito: &Efeito, dano: u32, rng: &mut dyn FonteAleatoria) -> Option<u32> {
+    match efeito {
+        Efeito::Sangramento(percentual, probabilidade) if rng.chance(*probabilidade) => {
+            Some(aplicar_percentual(dano, *percentual).max(1))
+        }
+        _ => None,
+    }
+}
+
+/// `percentual`% de `valor`, sem estourar com valores grandes digitados pelo jogador.
+fn aplicar_percentual(valor: u32, percentual: u32) -> u32 {
+    (valor as u64 * percentual as u64 / 100).min(u32::MAX as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpg_game::{itens::{Arma, Armadura}, personagens::Classe, raridade::Raridade};
+
+    fn personagem() -> Personagem {
+        Personagem::new(
+            "Alvo".to_string(), 50, 10, Classe::Guerreiro,
+            Armadura::new("Tunica".to_string(), 0, Raridade::Comum),
+            Arma::new("Espada".to_string(), 5, Classe::Guerreiro, Raridade::Comum, Efeito::Fisico),
+        )
+    }
+
+    #[test]
+    fn queimadura_sem_rodadas_nao_fica_ativa() {
+        let mut alvo = personagem();
+        let mut status = StatusAtivo::new();
+        let mut eventos = Vec::new();
+
+        status.aplicar(&Efeito::Queimadura(2, 0), &alvo, &mut eventos);
+        status.tick(&mut alvo, &mut eventos);
+
+        assert!(status.queimadura.is_none());
+        assert!(eventos.is_empty());
+        assert_eq!(alvo.vida, 50);
+    }
+
+    #[test]
+    fn percentuais_nao_estouram() {
+        let status = StatusAtivo { enfraquecimento: Some(10), ..StatusAtivo::new() };
+
+        assert_eq!(status.reduzir_dano(u32::MAX), (u32::MAX as u64 * 90 / 100) as u32);
+        assert_eq!(aplicar_percentual(u32::MAX, u32::MAX), u32::MAX);
+    }
+}